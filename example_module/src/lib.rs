@@ -3,8 +3,22 @@ extern crate napi_derive;
 
 use futures::prelude::*;
 use napi::bindgen_prelude::*;
+use napi::{sys, Env, JsFunction, JsObject, JsUnknown, NapiRaw, NapiValue};
+use std::ffi::CString;
+use std::os::raw::c_void;
+use std::ptr;
+use std::sync::atomic::{
+  AtomicBool, AtomicI32, AtomicU32, AtomicUsize, Ordering,
+};
+use std::time::Duration;
 use tokio::fs;
 
+static FINALIZED: AtomicU32 = AtomicU32::new(0);
+static EXPECTED_FINALIZED: AtomicU32 = AtomicU32::new(0);
+static CANCELLED_EXECUTED: AtomicU32 = AtomicU32::new(0);
+static CANCELLED_COMPLETED: AtomicU32 = AtomicU32::new(0);
+static CANCELLED_STATUS: AtomicI32 = AtomicI32::new(-1);
+
 #[napi]
 fn hello(name: String) -> String {
   println!("Hello, {}!", name);
@@ -28,6 +42,11 @@ impl Point {
   pub fn new(x: i32, y: i32) -> Point {
     Point { x, y }
   }
+
+  #[napi]
+  pub fn length_squared(&self) -> i32 {
+    self.x * self.x + self.y * self.y
+  }
 }
 
 #[napi]
@@ -42,3 +61,619 @@ async fn read_file_async(path: String) -> Result<Buffer> {
     })
     .await
 }
+
+unsafe extern "C" fn count_finalize(
+  _env: sys::napi_env,
+  _data: *mut c_void,
+  _hint: *mut c_void,
+) {
+  let count = FINALIZED.fetch_add(1, Ordering::SeqCst) + 1;
+  println!("finalized: {}", count);
+}
+
+#[napi]
+fn add_finalizer(env: Env, object: JsObject) -> Result<()> {
+  let status = unsafe {
+    sys::napi_add_finalizer(
+      env.raw(),
+      object.raw(),
+      std::ptr::null_mut(),
+      Some(count_finalize),
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+    )
+  };
+  check_status!(status)
+}
+
+#[napi]
+fn finalized_count() -> u32 {
+  FINALIZED.load(Ordering::SeqCst)
+}
+
+unsafe extern "C" fn check_finalized(_arg: *mut c_void) {
+  let finalized = FINALIZED.load(Ordering::SeqCst);
+  let expected = EXPECTED_FINALIZED.load(Ordering::SeqCst);
+  if finalized != expected {
+    eprintln!(
+      "expected {} finalizer(s) to run, got {}",
+      expected, finalized
+    );
+    std::process::exit(1);
+  }
+}
+
+/// Fails the process at exit unless `count` finalizers have run by then.
+#[napi]
+fn expect_finalized(env: Env, count: u32) -> Result<()> {
+  EXPECTED_FINALIZED.store(count, Ordering::SeqCst);
+  check_status!(unsafe {
+    sys::napi_add_env_cleanup_hook(
+      env.raw(),
+      Some(check_finalized),
+      ptr::null_mut(),
+    )
+  })
+}
+
+#[napi]
+fn call_and_wrap(env: Env, callback: JsFunction) -> Result<()> {
+  unsafe {
+    let mut recv = ptr::null_mut();
+    check_status!(sys::napi_get_undefined(env.raw(), &mut recv))?;
+    sys::napi_call_function(
+      env.raw(),
+      recv,
+      callback.raw(),
+      0,
+      ptr::null(),
+      ptr::null_mut(),
+    );
+
+    let mut pending = false;
+    check_status!(sys::napi_is_exception_pending(env.raw(), &mut pending))?;
+    if !pending {
+      return Ok(());
+    }
+    let mut error = ptr::null_mut();
+    check_status!(sys::napi_get_and_clear_last_exception(
+      env.raw(),
+      &mut error
+    ))?;
+    let error = JsUnknown::from_raw_unchecked(env.raw(), error);
+    let message = error.coerce_to_string()?.into_utf8()?;
+    Err(Error::new(
+      Status::GenericFailure,
+      format!("wrapped: {}", message.as_str()?),
+    ))
+  }
+}
+
+#[napi]
+fn is_instance(value: JsUnknown, constructor: JsFunction) -> Result<bool> {
+  value.instanceof(constructor)
+}
+
+/// Calls `callback` `count` times from another thread through a raw
+/// threadsafe function, without `call_js_cb` or `thread_finalize_cb`.
+#[napi]
+fn call_from_thread(env: Env, callback: JsFunction, count: u32) -> Result<()> {
+  let name = env.create_string("callFromThread")?;
+  let mut tsfn = ptr::null_mut();
+  check_status!(unsafe {
+    sys::napi_create_threadsafe_function(
+      env.raw(),
+      callback.raw(),
+      ptr::null_mut(),
+      name.raw(),
+      0,
+      1,
+      ptr::null_mut(),
+      None,
+      ptr::null_mut(),
+      None,
+      &mut tsfn,
+    )
+  })?;
+
+  let tsfn = tsfn as usize;
+  std::thread::spawn(move || unsafe {
+    let tsfn = tsfn as sys::napi_threadsafe_function;
+    for _ in 0..count {
+      sys::napi_call_threadsafe_function(
+        tsfn,
+        ptr::null_mut(),
+        sys::ThreadsafeFunctionCallMode::blocking,
+      );
+    }
+    sys::napi_release_threadsafe_function(
+      tsfn,
+      sys::ThreadsafeFunctionReleaseMode::release,
+    );
+  });
+  Ok(())
+}
+
+struct SumWork {
+  n: u32,
+  sum: u64,
+  callback: sys::napi_ref,
+  work: sys::napi_async_work,
+}
+
+unsafe extern "C" fn sum_execute(env: sys::napi_env, data: *mut c_void) {
+  let work = &mut *(data as *mut SumWork);
+  // The env must still be valid, even though `sum_async` has returned.
+  let mut instance_data = ptr::null_mut();
+  let status = sys::napi_get_instance_data(env, &mut instance_data);
+  assert_eq!(status, sys::Status::napi_ok);
+  work.sum = (1..=work.n as u64).sum();
+}
+
+unsafe extern "C" fn sum_complete(
+  env: sys::napi_env,
+  _status: sys::napi_status,
+  data: *mut c_void,
+) {
+  let work = Box::from_raw(data as *mut SumWork);
+  let mut callback = ptr::null_mut();
+  sys::napi_get_reference_value(env, work.callback, &mut callback);
+  let mut recv = ptr::null_mut();
+  sys::napi_get_undefined(env, &mut recv);
+  let mut sum = ptr::null_mut();
+  sys::napi_create_double(env, work.sum as f64, &mut sum);
+  sys::napi_call_function(env, recv, callback, 1, &sum, ptr::null_mut());
+  sys::napi_delete_reference(env, work.callback);
+  sys::napi_delete_async_work(env, work.work);
+}
+
+/// Sums `1..=n` on a worker thread through raw async work.
+#[napi]
+fn sum_async(env: Env, n: u32, callback: JsFunction) -> Result<()> {
+  let name = env.create_string("sumAsync")?;
+  unsafe {
+    let work = Box::into_raw(Box::new(SumWork {
+      n,
+      sum: 0,
+      callback: ptr::null_mut(),
+      work: ptr::null_mut(),
+    }));
+    check_status!(sys::napi_create_reference(
+      env.raw(),
+      callback.raw(),
+      1,
+      &mut (*work).callback,
+    ))?;
+    check_status!(sys::napi_create_async_work(
+      env.raw(),
+      ptr::null_mut(),
+      name.raw(),
+      Some(sum_execute),
+      Some(sum_complete),
+      work as *mut c_void,
+      &mut (*work).work,
+    ))?;
+    check_status!(sys::napi_queue_async_work(env.raw(), (*work).work))
+  }
+}
+
+unsafe extern "C" fn cancelled_execute(
+  _env: sys::napi_env,
+  _data: *mut c_void,
+) {
+  CANCELLED_EXECUTED.fetch_add(1, Ordering::SeqCst);
+}
+
+unsafe extern "C" fn cancelled_complete(
+  env: sys::napi_env,
+  status: sys::napi_status,
+  data: *mut c_void,
+) {
+  CANCELLED_STATUS.store(status, Ordering::SeqCst);
+  CANCELLED_COMPLETED.fetch_add(1, Ordering::SeqCst);
+  delete_work(env, status, data);
+}
+
+unsafe extern "C" fn check_cancelled(_arg: *mut c_void) {
+  let executed = CANCELLED_EXECUTED.load(Ordering::SeqCst);
+  let completed = CANCELLED_COMPLETED.load(Ordering::SeqCst);
+  let status = CANCELLED_STATUS.load(Ordering::SeqCst);
+  if executed != 0 || completed != 1 || status != sys::Status::napi_cancelled {
+    eprintln!(
+      "cancelAsyncWork: executed {} time(s), completed {} time(s) with status {}",
+      executed, completed, status
+    );
+    std::process::exit(1);
+  }
+  println!("cancelAsyncWork: completed once with napi_cancelled");
+}
+
+/// More async work than Node runs at once, or than this runtime does.
+const BLOCKERS: usize = 6;
+
+static BLOCKERS_RELEASED: AtomicBool = AtomicBool::new(false);
+
+unsafe extern "C" fn blocker_execute(_env: sys::napi_env, _data: *mut c_void) {
+  while !BLOCKERS_RELEASED.load(Ordering::SeqCst) {
+    std::thread::sleep(Duration::from_millis(1));
+  }
+}
+
+unsafe extern "C" fn delete_work(
+  env: sys::napi_env,
+  _status: sys::napi_status,
+  data: *mut c_void,
+) {
+  let work = Box::from_raw(data as *mut sys::napi_async_work);
+  sys::napi_delete_async_work(env, *work);
+}
+
+/// Creates async work whose data is a box holding the work, so that
+/// `complete` can delete it.
+unsafe fn create_async_work(
+  env: &Env,
+  execute: sys::napi_async_execute_callback,
+  complete: sys::napi_async_complete_callback,
+) -> Result<sys::napi_async_work> {
+  let name = env.create_string("cancelAsyncWork")?;
+  let work = Box::into_raw(Box::new(ptr::null_mut()));
+  check_status!(sys::napi_create_async_work(
+    env.raw(),
+    ptr::null_mut(),
+    name.raw(),
+    execute,
+    complete,
+    work as *mut c_void,
+    work,
+  ))?;
+  Ok(*work)
+}
+
+/// Queues async work behind blockers that keep every worker busy, and
+/// cancels it before it gets to run. The outcome is checked at exit, once
+/// the event loop has run out of work.
+#[napi]
+fn cancel_async_work(env: Env) -> Result<()> {
+  unsafe {
+    for _ in 0..BLOCKERS {
+      let blocker =
+        create_async_work(&env, Some(blocker_execute), Some(delete_work))?;
+      check_status!(sys::napi_queue_async_work(env.raw(), blocker))?;
+    }
+    let work = create_async_work(
+      &env,
+      Some(cancelled_execute),
+      Some(cancelled_complete),
+    )?;
+    check_status!(sys::napi_queue_async_work(env.raw(), work))?;
+    let status = sys::napi_cancel_async_work(env.raw(), work);
+    BLOCKERS_RELEASED.store(true, Ordering::SeqCst);
+    check_status!(status)?;
+    check_status!(sys::napi_add_env_cleanup_hook(
+      env.raw(),
+      Some(check_cancelled),
+      ptr::null_mut(),
+    ))
+  }
+}
+
+#[napi]
+fn get_all_property_names(
+  env: Env,
+  object: JsObject,
+  key_mode: i32,
+  key_filter: i32,
+  key_conversion: i32,
+) -> Result<JsUnknown> {
+  let mut result = ptr::null_mut();
+  check_status!(unsafe {
+    sys::napi_get_all_property_names(
+      env.raw(),
+      object.raw(),
+      key_mode,
+      key_filter,
+      key_conversion,
+      &mut result,
+    )
+  })?;
+  Ok(unsafe { JsUnknown::from_raw_unchecked(env.raw(), result) })
+}
+
+#[napi]
+fn create_buffer(env: Env, length: u32) -> Result<JsUnknown> {
+  let mut data = ptr::null_mut();
+  let mut result = ptr::null_mut();
+  check_status!(unsafe {
+    sys::napi_create_buffer(env.raw(), length as usize, &mut data, &mut result)
+  })?;
+  Ok(unsafe { JsUnknown::from_raw_unchecked(env.raw(), result) })
+}
+
+#[napi]
+fn create_buffer_copy(env: Env, contents: String) -> Result<JsUnknown> {
+  let mut result = ptr::null_mut();
+  check_status!(unsafe {
+    sys::napi_create_buffer_copy(
+      env.raw(),
+      contents.len(),
+      contents.as_ptr() as *const c_void,
+      ptr::null_mut(),
+      &mut result,
+    )
+  })?;
+  Ok(unsafe { JsUnknown::from_raw_unchecked(env.raw(), result) })
+}
+
+/// Backed by leaked memory, so that no finalizer is needed.
+#[napi]
+fn create_external_buffer(env: Env, contents: String) -> Result<JsUnknown> {
+  let contents = Box::leak(contents.into_bytes().into_boxed_slice());
+  let mut result = ptr::null_mut();
+  check_status!(unsafe {
+    sys::napi_create_external_buffer(
+      env.raw(),
+      contents.len(),
+      contents.as_mut_ptr() as *mut c_void,
+      None,
+      ptr::null_mut(),
+      &mut result,
+    )
+  })?;
+  Ok(unsafe { JsUnknown::from_raw_unchecked(env.raw(), result) })
+}
+
+/// Escapes a value from an outer escapable scope while an inner scope is
+/// still open, then checks that the same scope can't escape again.
+#[napi]
+fn escape_from_outer_scope(env: Env) -> Result<JsUnknown> {
+  unsafe {
+    let mut outer = ptr::null_mut();
+    check_status!(sys::napi_open_escapable_handle_scope(
+      env.raw(),
+      &mut outer
+    ))?;
+    let mut inner = ptr::null_mut();
+    check_status!(sys::napi_open_handle_scope(env.raw(), &mut inner))?;
+    let value = env.create_string("escaped")?;
+    let mut escaped = ptr::null_mut();
+    let status =
+      sys::napi_escape_handle(env.raw(), outer, value.raw(), &mut escaped);
+    let mut again = ptr::null_mut();
+    let twice =
+      sys::napi_escape_handle(env.raw(), outer, value.raw(), &mut again);
+    check_status!(sys::napi_close_handle_scope(env.raw(), inner))?;
+    check_status!(sys::napi_close_escapable_handle_scope(env.raw(), outer))?;
+    check_status!(status)?;
+    if twice != sys::Status::napi_escape_called_twice {
+      return Err(Error::new(
+        Status::GenericFailure,
+        format!(
+          "escaping twice: expected napi_escape_called_twice, got {}",
+          twice
+        ),
+      ));
+    }
+    Ok(JsUnknown::from_raw_unchecked(env.raw(), escaped))
+  }
+}
+
+unsafe extern "C" fn return_new_target(
+  env: sys::napi_env,
+  info: sys::napi_callback_info,
+) -> sys::napi_value {
+  let mut new_target = ptr::null_mut();
+  sys::napi_get_new_target(env, info, &mut new_target);
+  if new_target.is_null() {
+    sys::napi_get_undefined(env, &mut new_target);
+  }
+  new_target
+}
+
+/// Creates a function returning its `new.target`, which `new` returns in
+/// place of the constructed object.
+#[napi]
+fn create_new_target_function(env: Env) -> Result<JsUnknown> {
+  let name = "newTarget";
+  let mut result = ptr::null_mut();
+  check_status!(unsafe {
+    sys::napi_create_function(
+      env.raw(),
+      name.as_ptr() as *const _,
+      name.len(),
+      Some(return_new_target),
+      ptr::null_mut(),
+      &mut result,
+    )
+  })?;
+  Ok(unsafe { JsUnknown::from_raw_unchecked(env.raw(), result) })
+}
+
+#[napi]
+fn object_freeze(env: Env, object: JsObject) -> Result<()> {
+  check_status!(unsafe { sys::napi_object_freeze(env.raw(), object.raw()) })
+}
+
+#[napi]
+fn object_seal(env: Env, object: JsObject) -> Result<()> {
+  check_status!(unsafe { sys::napi_object_seal(env.raw(), object.raw()) })
+}
+
+/// Returns the status, so that failures can be checked from JS.
+#[napi]
+fn detach_arraybuffer(env: Env, value: JsUnknown) -> i32 {
+  unsafe { sys::napi_detach_arraybuffer(env.raw(), value.raw()) }
+}
+
+#[napi]
+fn is_detached_arraybuffer(env: Env, value: JsUnknown) -> Result<bool> {
+  let mut result = false;
+  check_status!(unsafe {
+    sys::napi_is_detached_arraybuffer(env.raw(), value.raw(), &mut result)
+  })?;
+  Ok(result)
+}
+
+/// Reports where the data of `value` starts relative to its buffer, along
+/// with its type and length.
+#[napi]
+fn get_typedarray_info(env: Env, value: JsUnknown) -> Result<JsObject> {
+  let mut typedarray_type = 0;
+  let mut length = 0;
+  let mut data = ptr::null_mut();
+  let mut arraybuffer = ptr::null_mut();
+  let mut byte_offset = 0;
+  let mut buffer_data = ptr::null_mut();
+  let mut buffer_length = 0;
+  unsafe {
+    check_status!(sys::napi_get_typedarray_info(
+      env.raw(),
+      value.raw(),
+      &mut typedarray_type,
+      &mut length,
+      &mut data,
+      &mut arraybuffer,
+      &mut byte_offset,
+    ))?;
+    check_status!(sys::napi_get_arraybuffer_info(
+      env.raw(),
+      arraybuffer,
+      &mut buffer_data,
+      &mut buffer_length,
+    ))?;
+  }
+
+  let mut info = env.create_object()?;
+  info.set_named_property("type", env.create_int32(typedarray_type)?)?;
+  info.set_named_property("length", env.create_uint32(length as u32)?)?;
+  info
+    .set_named_property("byteOffset", env.create_uint32(byte_offset as u32)?)?;
+  info.set_named_property(
+    "dataOffset",
+    env.create_int64(data as i64 - buffer_data as i64)?,
+  )?;
+  Ok(info)
+}
+
+/// Returns the status of calling `callback` with a null `argv` but a
+/// non-zero `argc`.
+#[napi]
+fn call_with_null_argv(env: Env, callback: JsFunction) -> Result<i32> {
+  unsafe {
+    let mut recv = ptr::null_mut();
+    check_status!(sys::napi_get_undefined(env.raw(), &mut recv))?;
+    Ok(sys::napi_call_function(
+      env.raw(),
+      recv,
+      callback.raw(),
+      1,
+      ptr::null(),
+      ptr::null_mut(),
+    ))
+  }
+}
+
+/// The reference made by `create_reference`, as a `napi_ref`.
+static REFERENCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the status, so that failures can be checked from JS.
+#[napi]
+fn create_reference(env: Env, value: JsUnknown, count: u32) -> i32 {
+  let mut reference = ptr::null_mut();
+  let status = unsafe {
+    sys::napi_create_reference(env.raw(), value.raw(), count, &mut reference)
+  };
+  if status == sys::Status::napi_ok {
+    REFERENCE.store(reference as usize, Ordering::SeqCst);
+  }
+  status
+}
+
+fn reference() -> sys::napi_ref {
+  REFERENCE.load(Ordering::SeqCst) as sys::napi_ref
+}
+
+#[napi]
+fn reference_ref(env: Env) -> Result<u32> {
+  let mut count = 0;
+  check_status!(unsafe {
+    sys::napi_reference_ref(env.raw(), reference(), &mut count)
+  })?;
+  Ok(count)
+}
+
+#[napi]
+fn reference_unref(env: Env) -> Result<u32> {
+  let mut count = 0;
+  check_status!(unsafe {
+    sys::napi_reference_unref(env.raw(), reference(), &mut count)
+  })?;
+  Ok(count)
+}
+
+/// Returns null once the target has been collected.
+#[napi]
+fn get_reference_value(env: Env) -> Result<JsUnknown> {
+  let mut result = ptr::null_mut();
+  unsafe {
+    check_status!(sys::napi_get_reference_value(
+      env.raw(),
+      reference(),
+      &mut result
+    ))?;
+    if result.is_null() {
+      check_status!(sys::napi_get_null(env.raw(), &mut result))?;
+    }
+    Ok(JsUnknown::from_raw_unchecked(env.raw(), result))
+  }
+}
+
+#[napi]
+fn delete_reference(env: Env) -> Result<()> {
+  check_status!(unsafe { sys::napi_delete_reference(env.raw(), reference()) })
+}
+
+const TYPE_TAGS: [sys::napi_type_tag; 2] = [
+  sys::napi_type_tag { lower: 1, upper: 2 },
+  sys::napi_type_tag { lower: 3, upper: 4 },
+];
+
+/// Returns the status, so that failures can be checked from JS.
+#[napi]
+fn type_tag_object(env: Env, object: JsObject, tag: u32) -> i32 {
+  unsafe {
+    sys::napi_type_tag_object(env.raw(), object.raw(), &TYPE_TAGS[tag as usize])
+  }
+}
+
+#[napi]
+fn check_object_type_tag(env: Env, object: JsObject, tag: u32) -> Result<bool> {
+  let mut result = false;
+  check_status!(unsafe {
+    sys::napi_check_object_type_tag(
+      env.raw(),
+      object.raw(),
+      &TYPE_TAGS[tag as usize],
+      &mut result,
+    )
+  })?;
+  Ok(result)
+}
+
+#[napi]
+fn throw_error(env: Env, code: String, message: String) -> Result<()> {
+  let code = CString::new(code)?;
+  let message = CString::new(message)?;
+  check_status!(unsafe {
+    sys::napi_throw_error(env.raw(), code.as_ptr(), message.as_ptr())
+  })
+}
+
+/// Returns the status, so that failures can be checked from JS.
+#[napi]
+fn create_error_with_code(env: Env, code: JsUnknown) -> Result<i32> {
+  let message = env.create_string("message")?;
+  let mut result = ptr::null_mut();
+  Ok(unsafe {
+    sys::napi_create_error(env.raw(), code.raw(), message.raw(), &mut result)
+  })
+}
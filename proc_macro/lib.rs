@@ -14,6 +14,18 @@ pub fn napi_sym(_attr: TokenStream, item: TokenStream) -> TokenStream {
     syn::ReturnType::Default => panic!("expected a return type"),
     syn::ReturnType::Type(_, ty) => quote! { #ty },
   };
+  // Like Node, every call made with an env records its status there.
+  let record_status = match inputs.first() {
+    Some(syn::FnArg::Typed(arg)) => match (&*arg.pat, &*arg.ty) {
+      (syn::Pat::Ident(pat), syn::Type::Path(ty))
+        if pat.ident == "env" && ty.path.is_ident("napi_env") =>
+      {
+        quote! { crate::env::set_last_error(env, status); }
+      }
+      _ => quote! {},
+    },
+    _ => quote! {},
+  };
   TokenStream::from(quote! {
       #[no_mangle]
       pub unsafe extern "C" fn #name(#inputs) -> napi_status {
@@ -22,13 +34,15 @@ pub fn napi_sym(_attr: TokenStream, item: TokenStream) -> TokenStream {
           result
         };
         let result = inner();
-        match result {
+        let status = match result {
           Ok(_) => napi_ok,
           Err(err) => {
             let status: napi_status = err.into();
             status
           },
-        }
+        };
+        #record_status
+        status
       }
   })
 }
@@ -0,0 +1,140 @@
+use deno_core::v8;
+use std::cell::RefCell;
+
+// Native calls each run in a fresh context, so looking builtins up on the
+// current global would get that context's, and user code may have
+// replaced the ones of the main context. They are captured once instead.
+struct Builtins {
+  own_keys: v8::Global<v8::Function>,
+  get_own_property_descriptor: v8::Global<v8::Function>,
+  define_property: v8::Global<v8::Function>,
+  freeze: v8::Global<v8::Function>,
+  seal: v8::Global<v8::Function>,
+  instance_of: v8::Global<v8::Function>,
+  buffer_prototype: v8::Global<v8::Object>,
+}
+
+thread_local! {
+  static BUILTINS: RefCell<Option<Builtins>> = const { RefCell::new(None) };
+}
+
+pub fn get_function<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  object: v8::Local<v8::Object>,
+  name: &str,
+) -> v8::Local<'s, v8::Function> {
+  let key = v8::String::new(scope, name).unwrap();
+  let value = object.get(scope, key.into()).unwrap();
+  v8::Local::<v8::Function>::try_from(value).unwrap()
+}
+
+fn get_object<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  object: v8::Local<v8::Object>,
+  name: &str,
+) -> v8::Local<'s, v8::Object> {
+  let key = v8::String::new(scope, name).unwrap();
+  let value = object.get(scope, key.into()).unwrap();
+  v8::Local::<v8::Object>::try_from(value).unwrap()
+}
+
+/// Captures the builtins before any user code gets a chance to replace
+/// them. Must be called after `core.js` has run, but before loading any
+/// module.
+pub fn init(scope: &mut v8::HandleScope) {
+  let context = scope.get_current_context();
+  let global = context.global(scope);
+  let reflect = get_object(scope, global, "Reflect");
+  let own_keys = get_function(scope, reflect, "ownKeys");
+  let get_own_property_descriptor =
+    get_function(scope, reflect, "getOwnPropertyDescriptor");
+  let object = get_object(scope, global, "Object");
+  let define_property = get_function(scope, object, "defineProperty");
+  let freeze = get_function(scope, object, "freeze");
+  let seal = get_function(scope, object, "seal");
+  // There's no V8 API for `instanceof` that honors `Symbol.hasInstance`.
+  let source = v8::String::new(
+    scope,
+    "(value, constructor) => value instanceof constructor",
+  )
+  .unwrap();
+  let script = v8::Script::compile(scope, source, None).unwrap();
+  let instance_of = script.run(scope).unwrap();
+  let instance_of = v8::Local::<v8::Function>::try_from(instance_of).unwrap();
+  let buffer = get_function(scope, global, "Buffer");
+  let buffer_prototype = get_object(scope, buffer.into(), "prototype");
+
+  let builtins = Builtins {
+    own_keys: v8::Global::new(scope, own_keys),
+    get_own_property_descriptor: v8::Global::new(
+      scope,
+      get_own_property_descriptor,
+    ),
+    define_property: v8::Global::new(scope, define_property),
+    freeze: v8::Global::new(scope, freeze),
+    seal: v8::Global::new(scope, seal),
+    instance_of: v8::Global::new(scope, instance_of),
+    buffer_prototype: v8::Global::new(scope, buffer_prototype),
+  };
+  BUILTINS.with(|cell| cell.replace(Some(builtins)));
+}
+
+fn get<'s, T>(
+  scope: &mut v8::HandleScope<'s>,
+  field: fn(&Builtins) -> &v8::Global<T>,
+) -> v8::Local<'s, T> {
+  BUILTINS.with(|cell| {
+    let builtins = cell.borrow();
+    let builtins = builtins.as_ref().expect("builtins::init was not called");
+    v8::Local::new(scope, field(builtins))
+  })
+}
+
+/// `Reflect.ownKeys`.
+pub fn own_keys<'s>(
+  scope: &mut v8::HandleScope<'s>,
+) -> v8::Local<'s, v8::Function> {
+  get(scope, |builtins| &builtins.own_keys)
+}
+
+/// `Reflect.getOwnPropertyDescriptor`.
+pub fn get_own_property_descriptor<'s>(
+  scope: &mut v8::HandleScope<'s>,
+) -> v8::Local<'s, v8::Function> {
+  get(scope, |builtins| &builtins.get_own_property_descriptor)
+}
+
+/// `Object.defineProperty`.
+pub fn define_property<'s>(
+  scope: &mut v8::HandleScope<'s>,
+) -> v8::Local<'s, v8::Function> {
+  get(scope, |builtins| &builtins.define_property)
+}
+
+/// `Object.freeze`.
+pub fn freeze<'s>(
+  scope: &mut v8::HandleScope<'s>,
+) -> v8::Local<'s, v8::Function> {
+  get(scope, |builtins| &builtins.freeze)
+}
+
+/// `Object.seal`.
+pub fn seal<'s>(
+  scope: &mut v8::HandleScope<'s>,
+) -> v8::Local<'s, v8::Function> {
+  get(scope, |builtins| &builtins.seal)
+}
+
+/// `(value, constructor) => value instanceof constructor`.
+pub fn instance_of<'s>(
+  scope: &mut v8::HandleScope<'s>,
+) -> v8::Local<'s, v8::Function> {
+  get(scope, |builtins| &builtins.instance_of)
+}
+
+/// `Buffer.prototype`, as defined by `core.js`.
+pub fn buffer_prototype<'s>(
+  scope: &mut v8::HandleScope<'s>,
+) -> v8::Local<'s, v8::Object> {
+  get(scope, |builtins| &builtins.buffer_prototype)
+}
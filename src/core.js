@@ -1,3 +1,9 @@
 function print(txt) {
   Deno.core.print(txt + "\n");
 }
+
+// Buffers created by addons get `Buffer.prototype`, which is captured
+// once this script has run. A host can extend it, or make it inherit from
+// a full Buffer implementation.
+class Buffer extends Uint8Array {}
+globalThis.Buffer = Buffer;
@@ -1,5 +1,95 @@
+use crate::event_loop;
 use crate::ffi::*;
+use crate::napi_fatal_error::napi_fatal_error;
 use deno_core::v8;
+use std::alloc::Layout;
+use std::cell::RefCell;
+use std::ffi::CString;
+
+pub type EnvCleanupHook = extern "C" fn(*const c_void);
+
+thread_local! {
+  /// Every env created on this thread, in load order.
+  static ENVS: RefCell<Vec<*mut EnvShared>> =
+    const { RefCell::new(Vec::new()) };
+}
+
+pub fn register_env(env: *mut EnvShared) {
+  ENVS.with(|envs| envs.borrow_mut().push(env));
+}
+
+/// Runs the cleanup hooks of every env, most recently loaded first, and
+/// starts their async cleanup hooks. Called once the event loop has
+/// finished; started async hooks keep it alive until they are removed.
+pub fn run_cleanup_hooks() {
+  let envs = ENVS.with(|envs| envs.borrow().clone());
+  for env in envs.into_iter().rev() {
+    unsafe { EnvShared::run_cleanup_hooks(env) };
+  }
+}
+
+/// Destroys every env, most recently loaded first. Called last, once the
+/// cleanup hooks are done.
+pub fn destroy_envs(scope: &mut v8::ContextScope<v8::HandleScope>) {
+  let envs = ENVS.with(|envs| std::mem::take(&mut *envs.borrow_mut()));
+  for env in envs.into_iter().rev() {
+    unsafe { EnvShared::destroy(scope, env) };
+  }
+}
+
+/// Node's message for each non-ok `napi_status`, starting at
+/// `napi_invalid_arg`.
+const ERROR_MESSAGES: [&[u8]; 21] = [
+  b"Invalid argument\0",
+  b"An object was expected\0",
+  b"A string was expected\0",
+  b"A string or symbol was expected\0",
+  b"A function was expected\0",
+  b"A number was expected\0",
+  b"A boolean was expected\0",
+  b"An array was expected\0",
+  b"Unknown failure\0",
+  b"An exception is pending\0",
+  b"The async work item was cancelled\0",
+  b"napi_escape_handle already called on scope\0",
+  b"Invalid handle scope usage\0",
+  b"Invalid callback scope usage\0",
+  b"Thread-safe function queue is full\0",
+  b"Thread-safe function handle is closing\0",
+  b"A bigint was expected\0",
+  b"A date was expected\0",
+  b"An arraybuffer was expected\0",
+  b"A detachable arraybuffer was expected\0",
+  b"Main thread would deadlock\0",
+];
+
+/// Records the status of the last napi call made with `env`. Called by
+/// `#[napi_sym]`.
+///
+/// # Safety
+///
+/// `env` must be null or point to a live `Env`.
+pub unsafe fn set_last_error(env: napi_env, status: napi_status) {
+  if env.is_null() {
+    return;
+  }
+  let shared = (*(env as *mut Env)).shared;
+  if shared.is_null() {
+    return;
+  }
+  let message = match status {
+    napi_ok => ptr::null(),
+    _ => ERROR_MESSAGES
+      .get(status as usize - 1)
+      .map_or(ptr::null(), |message| message.as_ptr() as *const c_char),
+  };
+  (*shared).last_error = napi_extended_error_info {
+    error_message: message,
+    engine_reserved: ptr::null_mut(),
+    engine_error_code: 0,
+    status_code: status,
+  };
+}
 
 #[repr(C)]
 #[derive(Debug)]
@@ -12,6 +102,26 @@ pub struct EnvShared {
   pub finalize: Option<napi_finalize>,
   pub finalize_hint: *mut c_void,
   pub filename: *const c_char,
+  /// The env the module was registered with. Unlike the env of a single
+  /// call, it stays allocated for as long as the module is loaded.
+  pub module_env: napi_env,
+  pub cleanup_hooks: Vec<(EnvCleanupHook, *const c_void)>,
+  pub async_cleanup_hooks: Vec<*mut AsyncCleanupHandle>,
+  /// Exception thrown during a napi call that hasn't been rethrown to JS
+  /// or cleared by the addon yet.
+  pub last_exception: Option<v8::Global<v8::Value>>,
+  /// Handed out by `napi_get_last_error_info`.
+  pub last_error: napi_extended_error_info,
+}
+
+/// Handle returned by `napi_add_async_cleanup_hook`. Freed when the addon
+/// removes the hook.
+#[derive(Debug)]
+pub struct AsyncCleanupHandle {
+  pub env: *mut EnvShared,
+  pub hook: napi_async_cleanup_hook,
+  pub data: *mut c_void,
+  pub started: bool,
 }
 
 impl EnvShared {
@@ -24,15 +134,177 @@ impl EnvShared {
       finalize: None,
       finalize_hint: std::ptr::null_mut(),
       filename: std::ptr::null(),
+      module_env: std::ptr::null_mut(),
+      cleanup_hooks: Vec::new(),
+      async_cleanup_hooks: Vec::new(),
+      last_exception: None,
+      last_error: napi_extended_error_info {
+        error_message: std::ptr::null(),
+        engine_reserved: std::ptr::null_mut(),
+        engine_error_code: 0,
+        status_code: napi_ok,
+      },
     }
   }
+
+  pub fn add_cleanup_hook(
+    &mut self,
+    hook: EnvCleanupHook,
+    data: *const c_void,
+  ) -> Result {
+    if self.cleanup_hooks.contains(&(hook, data)) {
+      return Err(Error::InvalidArg);
+    }
+    self.cleanup_hooks.push((hook, data));
+    Ok(())
+  }
+
+  pub fn remove_cleanup_hook(
+    &mut self,
+    hook: EnvCleanupHook,
+    data: *const c_void,
+  ) -> Result {
+    let index = self
+      .cleanup_hooks
+      .iter()
+      .position(|entry| *entry == (hook, data))
+      .ok_or(Error::InvalidArg)?;
+    self.cleanup_hooks.remove(index);
+    Ok(())
+  }
+
+  /// Runs hooks in reverse insertion order. A hook may remove hooks that
+  /// haven't run yet, so the list isn't borrowed while calling them.
+  ///
+  /// # Safety
+  ///
+  /// `env` must point to a live `EnvShared`.
+  pub unsafe fn run_cleanup_hooks(env: *mut EnvShared) {
+    while let Some((hook, data)) = (*env).cleanup_hooks.pop() {
+      hook(data);
+    }
+
+    let handles = (*env).async_cleanup_hooks.clone();
+    for handle in handles.into_iter().rev() {
+      // An earlier hook may have removed this one already.
+      if !(*env).async_cleanup_hooks.contains(&handle) || (*handle).started {
+        continue;
+      }
+      (*handle).started = true;
+      event_loop::ref_loop();
+      ((*handle).hook)(
+        handle as napi_async_cleanup_hook_handle,
+        (*handle).data,
+      );
+    }
+  }
+
+  /// Runs the instance data finalizer and frees an env allocated by
+  /// `dlopen`.
+  ///
+  /// # Safety
+  ///
+  /// `env` must have been allocated by `dlopen` and must not be used
+  /// afterwards.
+  pub unsafe fn destroy(
+    scope: &mut v8::ContextScope<v8::HandleScope>,
+    env: *mut EnvShared,
+  ) {
+    if let Some(data_finalize) = (*env).data_finalize.take() {
+      let mut napi_env = Env::with_shared(scope, env);
+      let napi_env_ptr = &mut napi_env as *mut _ as napi_env;
+      data_finalize(
+        napi_env_ptr,
+        (*env).instance_data,
+        (*env).data_finalize_hint,
+      );
+      napi_env.check_uncaught_exception();
+    }
+    if !(*env).filename.is_null() {
+      drop(CString::from_raw((*env).filename as *mut c_char));
+    }
+    std::ptr::drop_in_place(env);
+    std::alloc::dealloc(env as *mut u8, Layout::new::<EnvShared>());
+  }
+
+  pub fn add_async_cleanup_hook(
+    &mut self,
+    hook: napi_async_cleanup_hook,
+    data: *mut c_void,
+  ) -> *mut AsyncCleanupHandle {
+    let handle = Box::into_raw(Box::new(AsyncCleanupHandle {
+      env: self,
+      hook,
+      data,
+      started: false,
+    }));
+    self.async_cleanup_hooks.push(handle);
+    handle
+  }
+
+  /// # Safety
+  ///
+  /// `handle` must have been returned by `add_async_cleanup_hook` and not
+  /// removed yet.
+  pub unsafe fn remove_async_cleanup_hook(
+    handle: *mut AsyncCleanupHandle,
+  ) -> Result {
+    let env = &mut *(*handle).env;
+    let index = env
+      .async_cleanup_hooks
+      .iter()
+      .position(|entry| *entry == handle)
+      .ok_or(Error::InvalidArg)?;
+    env.async_cleanup_hooks.remove(index);
+    let handle = Box::from_raw(handle);
+    if handle.started {
+      event_loop::unref_loop();
+    }
+    Ok(())
+  }
+}
+
+/// Records the exception caught by `try_catch`, if any, as the pending
+/// exception of `env`.
+fn catch_exception(
+  env: *mut EnvShared,
+  try_catch: &mut v8::TryCatch<v8::HandleScope>,
+) -> Result {
+  if let Some(exception) = try_catch.exception() {
+    let exception = v8::Global::new(try_catch, exception);
+    unsafe { (*env).last_exception = Some(exception) };
+    return Err(Error::PendingException);
+  }
+  Ok(())
+}
+
+/// Handle scope opened by the addon. Boxed, as the handle given to the
+/// addon points to it.
+pub struct OpenHandleScope {
+  // The context scope is declared first so that it's dropped before the
+  // scope it was created from.
+  scope: v8::ContextScope<'static, v8::HandleScope<'static>>,
+  _handle_scope: Box<v8::HandleScope<'static>>,
+  kind: HandleScopeKind,
+  parent: *mut v8::ContextScope<'static, v8::HandleScope<'static>>,
+}
+
+enum HandleScopeKind {
+  Plain,
+  /// Like V8's `EscapableHandleScope`, reserves a handle in the parent
+  /// scope up front, so that the value can be escaped into it while inner
+  /// scopes are still open. `None` once used.
+  Escapable {
+    escape_slot: Option<v8::Local<'static, v8::Value>>,
+  },
 }
 
 #[repr(C)]
 #[derive(Debug)]
 pub struct Env<'a, 'b, 'c> {
   pub scope: &'a mut v8::ContextScope<'b, v8::HandleScope<'c>>,
-  pub open_handle_scopes: usize,
+  /// Innermost last. While one is open, `scope` points into it.
+  pub handle_scopes: Vec<*mut OpenHandleScope>,
   pub shared: *mut EnvShared,
 }
 
@@ -44,7 +316,20 @@ impl<'a, 'b, 'c> Env<'a, 'b, 'c> {
     Self {
       scope,
       shared: std::ptr::null_mut(),
-      open_handle_scopes: 0,
+      handle_scopes: Vec::new(),
+    }
+  }
+
+  /// Creates an env for callbacks that run outside of any native call,
+  /// e.g. from the event loop.
+  pub fn with_shared(
+    scope: &'a mut v8::ContextScope<'b, v8::HandleScope<'c>>,
+    shared: *mut EnvShared,
+  ) -> Self {
+    Self {
+      scope,
+      shared,
+      handle_scopes: Vec::new(),
     }
   }
 
@@ -55,7 +340,7 @@ impl<'a, 'b, 'c> Env<'a, 'b, 'c> {
     Self {
       scope,
       shared: self.shared,
-      open_handle_scopes: self.open_handle_scopes,
+      handle_scopes: Vec::new(),
     }
   }
 
@@ -66,4 +351,157 @@ impl<'a, 'b, 'c> Env<'a, 'b, 'c> {
   pub fn shared_mut(&self) -> &mut EnvShared {
     unsafe { &mut *self.shared }
   }
+
+  pub fn open_handle_scope(&mut self, escapable: bool) -> *mut OpenHandleScope {
+    unsafe {
+      let parent = &mut *self.scope as *mut v8::ContextScope<v8::HandleScope>;
+      let context = self.scope.get_current_context();
+      let kind = if escapable {
+        let undefined = v8::undefined(self.scope);
+        let escape_slot: v8::Local<v8::Value> =
+          v8::Local::new(self.scope, undefined).into();
+        HandleScopeKind::Escapable {
+          escape_slot: Some(transmute::<
+            v8::Local<v8::Value>,
+            v8::Local<'static, v8::Value>,
+          >(escape_slot)),
+        }
+      } else {
+        HandleScopeKind::Plain
+      };
+      let mut handle_scope: Box<v8::HandleScope<'static>> =
+        Box::new(transmute::<v8::HandleScope<'_>, v8::HandleScope<'static>>(
+          v8::HandleScope::new(self.scope),
+        ));
+      let scope = v8::ContextScope::new(&mut *handle_scope, context);
+      let open = Box::into_raw(Box::new(OpenHandleScope {
+        scope: transmute::<
+          v8::ContextScope<'_, v8::HandleScope<'static>>,
+          v8::ContextScope<'static, v8::HandleScope<'static>>,
+        >(scope),
+        _handle_scope: handle_scope,
+        kind,
+        parent: parent as *mut _,
+      }));
+      self.scope = transmute::<
+        &mut v8::ContextScope<v8::HandleScope>,
+        &'a mut v8::ContextScope<'b, v8::HandleScope<'c>>,
+      >(&mut (*open).scope);
+      self.handle_scopes.push(open);
+      open
+    }
+  }
+
+  /// Scopes must be closed in the reverse order they were opened in.
+  pub fn close_handle_scope(&mut self, scope: *mut OpenHandleScope) -> Result {
+    if scope.is_null() {
+      return Err(Error::InvalidArg);
+    }
+    if self.handle_scopes.last() != Some(&scope) {
+      return Err(Error::HandleScopeMismatch);
+    }
+    // Only ever free the pointer this env handed out itself.
+    let scope = self.handle_scopes.pop().unwrap();
+    unsafe {
+      let scope = Box::from_raw(scope);
+      self.scope = &mut *scope
+        .parent
+        .cast::<v8::ContextScope<'b, v8::HandleScope<'c>>>();
+    }
+    Ok(())
+  }
+
+  /// Scopes the addon left open would outlive the scope they were opened
+  /// in. Like Node, this is a fatal error.
+  pub fn close_unbalanced_handle_scopes(&mut self) {
+    if self.handle_scopes.is_empty() {
+      return;
+    }
+    let message = format!(
+      "N-API callback returned with {} open handle scope(s)",
+      self.handle_scopes.len()
+    );
+    while let Some(&scope) = self.handle_scopes.last() {
+      self.close_handle_scope(scope).unwrap();
+    }
+    unsafe {
+      napi_fatal_error(
+        ptr::null(),
+        0,
+        message.as_ptr() as *const c_char,
+        message.len() as isize,
+      )
+    }
+  }
+
+  /// Any open escapable scope can escape a value, but only once.
+  ///
+  /// # Safety
+  ///
+  /// `value` must be a handle that is still alive.
+  pub unsafe fn escape_handle(
+    &mut self,
+    scope: *mut OpenHandleScope,
+    value: v8::Local<v8::Value>,
+  ) -> std::result::Result<v8::Local<'static, v8::Value>, Error> {
+    if !self.handle_scopes.contains(&scope) {
+      return Err(Error::InvalidArg);
+    }
+    match &mut (*scope).kind {
+      HandleScopeKind::Escapable { escape_slot } => {
+        let escape_slot = escape_slot.take().ok_or(Error::EscapeCalledTwice)?;
+        // What `EscapableHandleScope::Escape` does: the reserved handle is
+        // made to point to the escaped value.
+        let slot = transmute::<v8::Local<v8::Value>, *mut usize>(escape_slot);
+        *slot = *transmute::<v8::Local<v8::Value>, *const usize>(value);
+        Ok(escape_slot)
+      }
+      HandleScopeKind::Plain => Err(Error::InvalidArg),
+    }
+  }
+
+  /// Runs `f`, which may run JS, under a `TryCatch`. An exception thrown
+  /// by it becomes the pending exception of the env, and an empty result
+  /// without one is a `GenericFailure`.
+  pub fn try_catch<T>(
+    &mut self,
+    f: impl FnOnce(&mut v8::TryCatch<v8::HandleScope<'c>>) -> Option<T>,
+  ) -> std::result::Result<T, Error> {
+    let shared = self.shared;
+    let try_catch = &mut v8::TryCatch::new(&mut *self.scope);
+    let result = f(try_catch);
+    catch_exception(shared, try_catch)?;
+    result.ok_or(Error::GenericFailure)
+  }
+
+  /// Node refuses to run JS while an earlier exception is still pending.
+  pub fn check_pending_exception(&self) -> Result {
+    if self.shared().last_exception.is_some() {
+      return Err(Error::PendingException);
+    }
+    Ok(())
+  }
+
+  /// Makes `exception` the pending exception, to be rethrown once control
+  /// returns to JS.
+  pub fn set_pending_exception(&mut self, exception: v8::Local<v8::Value>) {
+    let exception = v8::Global::new(self.scope, exception);
+    self.shared_mut().last_exception = Some(exception);
+  }
+
+  /// Called after invoking the addon outside of any JS call, e.g. from a
+  /// finalizer, where a pending exception has nowhere to propagate to.
+  pub fn check_uncaught_exception(&mut self) {
+    if let Some(exception) = self.shared_mut().last_exception.take() {
+      let exception = v8::Local::new(self.scope, exception);
+      eprintln!("Uncaught {}", exception.to_rust_string_lossy(self.scope));
+      std::process::exit(1);
+    }
+  }
+}
+
+impl Drop for Env<'_, '_, '_> {
+  fn drop(&mut self) {
+    self.close_unbalanced_handle_scopes();
+  }
 }
@@ -0,0 +1,103 @@
+use deno_core::error::AnyError;
+use deno_core::v8;
+use deno_core::JsRuntime;
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::task::Context;
+use std::task::Poll;
+use tokio::sync::mpsc;
+
+/// Callback queued from any thread to be run on the JS thread.
+pub type PendingCallback =
+  Box<dyn FnOnce(&mut v8::ContextScope<v8::HandleScope>) + Send>;
+
+/// Handle used to queue callbacks onto the JS thread from other threads.
+#[derive(Clone)]
+pub struct LoopSender(mpsc::UnboundedSender<PendingCallback>);
+
+impl LoopSender {
+  pub fn send(&self, callback: PendingCallback) {
+    // The receiver only goes away when the JS thread exits.
+    let _ = self.0.send(callback);
+  }
+}
+
+struct EventLoop {
+  sender: mpsc::UnboundedSender<PendingCallback>,
+  receiver: RefCell<mpsc::UnboundedReceiver<PendingCallback>>,
+  // Number of handles (ref'd threadsafe functions, queued async work)
+  // that keep the loop alive after deno_core runs out of work.
+  refs: Cell<usize>,
+}
+
+thread_local! {
+  static EVENT_LOOP: EventLoop = {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    EventLoop {
+      sender,
+      receiver: RefCell::new(receiver),
+      refs: Cell::new(0),
+    }
+  };
+}
+
+/// Must be called from the JS thread.
+pub fn sender() -> LoopSender {
+  EVENT_LOOP.with(|event_loop| LoopSender(event_loop.sender.clone()))
+}
+
+pub fn ref_loop() {
+  EVENT_LOOP.with(|event_loop| event_loop.refs.set(event_loop.refs.get() + 1));
+}
+
+pub fn unref_loop() {
+  EVENT_LOOP.with(|event_loop| event_loop.refs.set(event_loop.refs.get() - 1));
+}
+
+fn run_pending_callbacks(runtime: &mut JsRuntime, cx: &mut Context) -> bool {
+  let mut callbacks = vec![];
+  EVENT_LOOP.with(|event_loop| {
+    let mut receiver = event_loop.receiver.borrow_mut();
+    while let Poll::Ready(Some(callback)) = receiver.poll_recv(cx) {
+      callbacks.push(callback);
+    }
+  });
+
+  if callbacks.is_empty() {
+    return false;
+  }
+
+  let scope = &mut runtime.handle_scope();
+  let context = scope.get_current_context();
+  let scope = &mut v8::ContextScope::new(scope, context);
+  for callback in callbacks {
+    callback(scope);
+  }
+  // Callbacks usually settle promises from native code, so nothing else
+  // would run the reactions.
+  scope.perform_microtask_checkpoint();
+  true
+}
+
+/// Polls deno_core's event loop along with N-API callbacks queued on the
+/// JS thread. Resolves once both run out of work and no handle keeps the
+/// loop alive.
+pub fn poll_event_loop(
+  runtime: &mut JsRuntime,
+  cx: &mut Context,
+) -> Poll<Result<(), AnyError>> {
+  loop {
+    let poll = runtime.poll_event_loop(cx, false);
+    if let Poll::Ready(Err(err)) = poll {
+      return Poll::Ready(Err(err));
+    }
+
+    if !run_pending_callbacks(runtime, cx) {
+      let refs = EVENT_LOOP.with(|event_loop| event_loop.refs.get());
+      return match poll {
+        Poll::Ready(_) if refs == 0 => Poll::Ready(Ok(())),
+        _ => Poll::Pending,
+      };
+    }
+  }
+}
@@ -112,7 +112,7 @@ pub const napi_bigint: napi_valuetype = 9;
 pub type napi_threadsafe_function_release_mode = i32;
 
 pub const napi_tsfn_release: napi_threadsafe_function_release_mode = 0;
-pub const napi_tsfn_abortext: napi_threadsafe_function_release_mode = 1;
+pub const napi_tsfn_abort: napi_threadsafe_function_release_mode = 1;
 
 pub type napi_threadsafe_function_call_mode = i32;
 
@@ -152,6 +152,8 @@ pub const napi_float64_array: napi_typedarray_type = 8;
 pub const napi_bigint64_array: napi_typedarray_type = 9;
 pub const napi_biguint64_array: napi_typedarray_type = 10;
 
+#[repr(C)]
+#[derive(Debug)]
 pub struct napi_type_tag {
   pub lower: u64,
   pub upper: u64,
@@ -179,8 +181,10 @@ pub type napi_threadsafe_function_call_js = unsafe extern "C" fn(
   data: *mut c_void,
 );
 
-pub type napi_async_cleanup_hook =
-  unsafe extern "C" fn(env: napi_env, data: *mut c_void);
+pub type napi_async_cleanup_hook = unsafe extern "C" fn(
+  handle: napi_async_cleanup_hook_handle,
+  data: *mut c_void,
+);
 
 pub type napi_property_attributes = i32;
 
@@ -199,9 +203,9 @@ pub const napi_default_jsproperty: napi_property_attributes =
 pub struct napi_property_descriptor {
   pub utf8name: *const c_char,
   pub name: napi_value,
-  pub method: napi_callback,
-  pub getter: napi_callback,
-  pub setter: napi_callback,
+  pub method: Option<napi_callback>,
+  pub getter: Option<napi_callback>,
+  pub setter: Option<napi_callback>,
   pub value: napi_value,
   pub attributes: napi_property_attributes,
   pub data: *mut c_void,
@@ -10,28 +10,18 @@ pub struct CallbackInfo {
   pub args: *const c_void,
 }
 
-pub unsafe fn create_function<'a>(
-  env: &'a mut Env,
-  name: Option<&str>,
-  cb: napi_callback,
-  cb_info: napi_callback_info,
-) -> v8::Local<'a, v8::Function> {
-  let method_ptr = v8::External::new(env.scope, std::mem::transmute(cb));
-  let cb_info_ext = v8::External::new(env.scope, std::mem::transmute(cb_info));
-  let env_ptr = env as *mut _ as *mut c_void;
-  let env_ext = v8::External::new(env.scope, env_ptr);
-
-  let data_array = v8::Array::new_with_elements(
-    env.scope,
-    &[method_ptr.into(), cb_info_ext.into(), env_ext.into()],
-  );
-
-  let function = v8::Function::builder(
-    |handle_scope: &mut v8::HandleScope,
-     args: v8::FunctionCallbackArguments,
-     mut rv: v8::ReturnValue| {
-      let context = v8::Context::new(handle_scope);
-      let scope = &mut v8::ContextScope::new(handle_scope, context);
+/// Trampoline shared by functions and function templates. `args.data()`
+/// holds the callback, its data and the env.
+fn call_native_function(
+  handle_scope: &mut v8::HandleScope,
+  args: v8::FunctionCallbackArguments,
+  mut rv: v8::ReturnValue,
+) {
+  let shared = {
+    let try_catch = &mut v8::TryCatch::new(handle_scope);
+    let shared = {
+      let context = v8::Context::new(try_catch);
+      let scope = &mut v8::ContextScope::new(try_catch, context);
 
       let data = args.data().unwrap();
       let data_array = v8::Local::<v8::Array>::try_from(data).unwrap();
@@ -56,7 +46,8 @@ pub unsafe fn create_function<'a>(
       .unwrap();
       let env_ptr = env_ptr.value() as *mut Env;
 
-      let mut env = (&mut *(env_ptr)).with_new_scope(scope);
+      let mut env = unsafe { (&mut *(env_ptr)).with_new_scope(scope) };
+      let shared = env.shared;
       let env_ptr = &mut env as *mut _ as *mut c_void;
 
       let mut info = CallbackInfo {
@@ -71,11 +62,72 @@ pub unsafe fn create_function<'a>(
       let value = unsafe { cb(env_ptr, info_ptr) };
       let value = unsafe { std::mem::transmute(value) };
       rv.set(value);
-    },
+      shared
+    };
+
+    // Anything that escaped a napi call without being recorded.
+    if let Some(exception) = try_catch.exception() {
+      if unsafe { (*shared).last_exception.is_none() } {
+        let exception = v8::Global::new(try_catch, exception);
+        unsafe { (*shared).last_exception = Some(exception) };
+      }
+    }
+    shared
+  };
+
+  if let Some(exception) = unsafe { (*shared).last_exception.take() } {
+    let exception = v8::Local::new(handle_scope, exception);
+    handle_scope.throw_exception(exception);
+  }
+}
+
+/// `new.target` of a call, or undefined unless it was called with `new`.
+///
+/// rusty_v8 doesn't bind `FunctionCallbackInfo::NewTarget()`, which is
+/// inline in v8.h and only reads a slot of the `implicit_args` array that
+/// `v8::FunctionCallbackInfo` starts with. This does the same.
+///
+/// # Safety
+///
+/// `args` must be the arguments of a call that is still running.
+pub unsafe fn new_target<'s>(
+  args: &v8::FunctionCallbackArguments<'s>,
+) -> v8::Local<'s, v8::Value> {
+  // From `v8-function-callback.h`.
+  const NEW_TARGET_INDEX: usize = 5;
+
+  // `FunctionCallbackArguments` only holds a pointer to the info.
+  assert_eq!(
+    std::mem::size_of::<v8::FunctionCallbackArguments>(),
+    std::mem::size_of::<*const v8::FunctionCallbackInfo>()
+  );
+  let info = *(args as *const _ as *const *const v8::FunctionCallbackInfo);
+  let implicit_args = *(info as *const *const usize);
+  std::mem::transmute::<*const usize, v8::Local<v8::Value>>(
+    implicit_args.add(NEW_TARGET_INDEX),
   )
-  .data(data_array.into())
-  .build(env.scope)
-  .unwrap();
+}
+
+pub unsafe fn create_function<'a>(
+  env: &'a mut Env,
+  name: Option<&str>,
+  cb: napi_callback,
+  cb_info: napi_callback_info,
+) -> v8::Local<'a, v8::Function> {
+  let method_ptr = v8::External::new(env.scope, std::mem::transmute(cb));
+  let cb_info_ext = v8::External::new(env.scope, std::mem::transmute(cb_info));
+  let env_ptr = env as *mut _ as *mut c_void;
+  let env_ext = v8::External::new(env.scope, env_ptr);
+
+  let data_array = v8::Array::new_with_elements(
+    env.scope,
+    &[method_ptr.into(), cb_info_ext.into(), env_ext.into()],
+  );
+
+  let function = v8::Function::builder(call_native_function)
+    .data(data_array.into())
+    .build(env.scope)
+    .unwrap();
 
   if let Some(name) = name {
     let v8str = v8::String::new(env.scope, name).unwrap();
@@ -101,55 +153,9 @@ pub unsafe fn create_function_template<'a>(
     &[method_ptr.into(), cb_info_ext.into(), env_ext.into()],
   );
 
-  let function = v8::FunctionTemplate::builder(
-    |handle_scope: &mut v8::HandleScope,
-     args: v8::FunctionCallbackArguments,
-     mut rv: v8::ReturnValue| {
-      let context = v8::Context::new(handle_scope);
-      let scope = &mut v8::ContextScope::new(handle_scope, context);
-
-      let data = args.data().unwrap();
-      let data_array = v8::Local::<v8::Array>::try_from(data).unwrap();
-
-      let method_ptr = v8::Local::<v8::External>::try_from(
-        data_array.get_index(scope, 0).unwrap(),
-      )
-      .unwrap();
-      let cb: napi_callback =
-        unsafe { std::mem::transmute(method_ptr.value()) };
-
-      let cb_info_ptr = v8::Local::<v8::External>::try_from(
-        data_array.get_index(scope, 1).unwrap(),
-      )
-      .unwrap();
-      let cb_info: napi_callback_info =
-        unsafe { std::mem::transmute(cb_info_ptr.value()) };
-
-      let env_ptr = v8::Local::<v8::External>::try_from(
-        data_array.get_index(scope, 2).unwrap(),
-      )
-      .unwrap();
-      let env_ptr = env_ptr.value() as *mut Env;
-
-      let mut env = (&mut *(env_ptr)).with_new_scope(scope);
-      let env_ptr = &mut env as *mut _ as *mut c_void;
-
-      let mut info = CallbackInfo {
-        env: env_ptr,
-        cb,
-        cb_info,
-        args: &args as *const _ as *const c_void,
-      };
-
-      let info_ptr = &mut info as *mut _ as *mut c_void;
-
-      let value = unsafe { cb(env_ptr, info_ptr) };
-      let value = unsafe { std::mem::transmute(value) };
-      rv.set(value);
-    },
-  )
-  .data(data_array.into())
-  .build(env.scope);
+  let function = v8::FunctionTemplate::builder(call_native_function)
+    .data(data_array.into())
+    .build(env.scope);
 
   if let Some(name) = name {
     let v8str = v8::String::new(env.scope, name).unwrap();
@@ -8,6 +8,7 @@
 extern crate napi_sym;
 
 use std::ffi::CString;
+use std::time::Duration;
 
 use env::EnvShared;
 #[cfg(unix)]
@@ -16,14 +17,20 @@ use libloading::os::unix::*;
 #[cfg(windows)]
 use libloading::os::windows::*;
 
+pub mod builtins;
 pub mod env;
+pub mod event_loop;
 pub mod ffi;
 pub mod function;
+pub mod napi_acquire_threadsafe_function;
+pub mod napi_add_async_cleanup_hook;
 pub mod napi_add_env_cleanup_hook;
+pub mod napi_add_finalizer;
 pub mod napi_adjust_external_memory;
 pub mod napi_call_function;
 pub mod napi_call_threadsafe_function;
 pub mod napi_cancel_async_work;
+pub mod napi_check_object_type_tag;
 pub mod napi_close_escapable_handle_scope;
 pub mod napi_close_handle_scope;
 pub mod napi_coerce_to_bool;
@@ -91,6 +98,7 @@ pub mod napi_get_property;
 pub mod napi_get_property_names;
 pub mod napi_get_prototype;
 pub mod napi_get_reference_value;
+pub mod napi_get_threadsafe_function_context;
 pub mod napi_get_typedarray_info;
 pub mod napi_get_undefined;
 pub mod napi_get_value_bigint_int64;
@@ -121,6 +129,8 @@ pub mod napi_is_promise;
 pub mod napi_is_typedarray;
 pub mod napi_module_register;
 pub mod napi_new_instance;
+pub mod napi_object_freeze;
+pub mod napi_object_seal;
 pub mod napi_open_escapable_handle_scope;
 pub mod napi_open_handle_scope;
 pub mod napi_queue_async_work;
@@ -129,6 +139,7 @@ pub mod napi_reference_ref;
 pub mod napi_reference_unref;
 pub mod napi_reject_deferred;
 pub mod napi_release_threadsafe_function;
+pub mod napi_remove_async_cleanup_hook;
 pub mod napi_remove_env_cleanup_hook;
 pub mod napi_remove_wrap;
 pub mod napi_resolve_deferred;
@@ -142,6 +153,7 @@ pub mod napi_throw;
 pub mod napi_throw_error;
 pub mod napi_throw_range_error;
 pub mod napi_throw_type_error;
+pub mod napi_type_tag_object;
 pub mod napi_typeof;
 pub mod napi_unref_threadsafe_function;
 pub mod napi_unwrap;
@@ -150,16 +162,38 @@ pub mod node_api_create_syntax_error;
 pub mod node_api_get_module_file_name;
 pub mod node_api_throw_syntax_error;
 pub mod util;
+pub mod weak;
 
+use deno_core::futures::future::poll_fn;
 use deno_core::JsRuntime;
 
 use crate::env::Env;
 use crate::ffi::*;
 use deno_core::v8;
 
+const ASYNC_CLEANUP_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[tokio::main]
 async fn main() {
+  // Flags before the script path are passed on to V8, e.g. `--expose-gc`.
+  let mut args = std::env::args().skip(1).peekable();
+  let mut v8_flags = vec![];
+  while let Some(flag) = args.next_if(|arg| arg.starts_with("--")) {
+    v8_flags.push(flag);
+  }
+  if !v8_flags.is_empty() {
+    let mut flags = vec![String::new()];
+    flags.extend(v8_flags);
+    if let Some(flag) = deno_core::v8_set_flags(flags).get(1) {
+      eprintln!("error: V8 did not recognize flag '{}'", flag);
+      std::process::exit(1);
+    }
+  }
+
   let mut runtime = JsRuntime::new(Default::default());
+  runtime
+    .execute_script("core.js", include_str!("core.js"))
+    .unwrap();
 
   {
     let mut scope = &mut runtime.handle_scope();
@@ -194,12 +228,13 @@ async fn main() {
             as *mut EnvShared
         };
         let mut env_shared = EnvShared::new(napi_wrap);
+        // Freed along with the env, see `EnvShared::destroy`.
         let cstr = CString::new(path.clone()).unwrap();
-        env_shared.filename = cstr.as_ptr();
-        std::mem::forget(cstr);
+        env_shared.filename = cstr.into_raw();
         unsafe {
           env_shared_ptr.write(env_shared);
         }
+        env::register_env(env_shared_ptr);
 
         let env_ptr = unsafe {
           std::alloc::alloc(std::alloc::Layout::new::<Env>()) as napi_env
@@ -208,6 +243,7 @@ async fn main() {
         env.shared = env_shared_ptr;
         unsafe {
           (env_ptr as *mut Env).write(env);
+          (*env_shared_ptr).module_env = env_ptr;
         }
 
         #[cfg(unix)]
@@ -268,6 +304,15 @@ async fn main() {
           }
         });
 
+        unsafe { (*(env_ptr as *mut Env)).close_unbalanced_handle_scopes() };
+
+        // Rethrow anything the module threw while registering.
+        let exception = unsafe { (*env_shared_ptr).last_exception.take() };
+        if let Some(exception) = exception {
+          let exception = v8::Local::new(scope, exception);
+          scope.throw_exception(exception);
+        }
+
         std::mem::forget(library);
       },
     )
@@ -279,17 +324,14 @@ async fn main() {
     global
       .set(inner_scope, dlopen_name.into(), dlopen_func.into())
       .unwrap();
+
+    builtins::init(inner_scope);
+    weak::init(inner_scope);
   }
 
-  let filename = std::env::args()
-    .nth(1)
-    .unwrap_or(String::from("./test/example.js"));
+  let filename = args.next().unwrap_or(String::from("./test/example.js"));
   let source_code = std::fs::read_to_string(&filename).unwrap();
 
-  runtime
-    .execute_script("core.js", include_str!("core.js"))
-    .unwrap();
-
   match runtime.execute_script(&filename, &source_code) {
     Ok(_) => {}
     Err(e) => {
@@ -297,4 +339,38 @@ async fn main() {
       std::process::exit(1);
     }
   }
+
+  let result =
+    poll_fn(|cx| event_loop::poll_event_loop(&mut runtime, cx)).await;
+  if let Err(e) = result {
+    eprintln!("{}", e);
+    std::process::exit(1);
+  }
+
+  env::run_cleanup_hooks();
+
+  // Async cleanup hooks complete from the event loop, but a hook that
+  // never removes itself shouldn't keep the process alive forever.
+  let result = tokio::time::timeout(
+    ASYNC_CLEANUP_TIMEOUT,
+    poll_fn(|cx| event_loop::poll_event_loop(&mut runtime, cx)),
+  )
+  .await;
+  match result {
+    Ok(Ok(())) => {}
+    Ok(Err(e)) => {
+      eprintln!("{}", e);
+      std::process::exit(1);
+    }
+    Err(_) => {
+      eprintln!("warning: timed out waiting for async cleanup hooks");
+    }
+  }
+
+  {
+    let scope = &mut runtime.handle_scope();
+    let context = scope.get_current_context();
+    let scope = &mut v8::ContextScope::new(scope, context);
+    env::destroy_envs(scope);
+  }
 }
@@ -0,0 +1,11 @@
+use crate::ffi::*;
+use crate::napi_create_threadsafe_function::ThreadSafeFunction;
+
+#[napi_sym]
+fn napi_acquire_threadsafe_function(func: napi_threadsafe_function) -> Result {
+  if func.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let tsfn = &*(func as *const ThreadSafeFunction);
+  tsfn.acquire()
+}
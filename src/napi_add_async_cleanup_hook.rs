@@ -0,0 +1,18 @@
+use crate::env::Env;
+use crate::ffi::*;
+
+#[napi_sym]
+fn napi_add_async_cleanup_hook(
+  env: napi_env,
+  hook: Option<napi_async_cleanup_hook>,
+  arg: *mut c_void,
+  remove_handle: *mut napi_async_cleanup_hook_handle,
+) -> Result {
+  let env = &mut *(env as *mut Env);
+  let hook = hook.ok_or(Error::InvalidArg)?;
+  let handle = env.shared_mut().add_async_cleanup_hook(hook, arg);
+  if !remove_handle.is_null() {
+    *remove_handle = handle as napi_async_cleanup_hook_handle;
+  }
+  Ok(())
+}
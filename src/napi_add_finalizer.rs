@@ -0,0 +1,34 @@
+use crate::env::Env;
+use crate::ffi::*;
+use crate::napi_create_reference::Reference;
+use crate::weak;
+use crate::weak::Finalizer;
+use deno_core::v8;
+
+#[napi_sym]
+fn napi_add_finalizer(
+  env: napi_env,
+  js_object: napi_value,
+  finalize_data: *mut c_void,
+  finalize_cb: Option<napi_finalize>,
+  finalize_hint: *mut c_void,
+  result: *mut napi_ref,
+) -> Result {
+  let env = &mut *(env as *mut Env);
+  if js_object.is_null() || finalize_cb.is_none() {
+    return Err(Error::InvalidArg);
+  }
+  let value: v8::Local<v8::Value> = transmute(js_object);
+  let obj = v8::Local::<v8::Object>::try_from(value)
+    .map_err(|_| Error::ObjectExpected)?;
+
+  let finalizer =
+    Finalizer::new(env.shared, finalize_cb, finalize_data, finalize_hint);
+  weak::add_finalizer(env.scope, obj, finalizer);
+
+  if !result.is_null() {
+    let reference = Reference::new(env.scope, obj.into(), 0);
+    *result = Box::into_raw(Box::new(reference)) as napi_ref;
+  }
+  Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::env::Env;
 use crate::ffi::*;
+use crate::util::get_args;
 use deno_core::v8;
 
 #[napi_sym]
@@ -11,14 +12,16 @@ fn napi_call_function(
   argv: *const napi_value,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let recv: v8::Local<v8::Value> = std::mem::transmute(recv);
   let func: v8::Local<v8::Value> = std::mem::transmute(func);
-  let func = v8::Local::<v8::Function>::try_from(func).unwrap();
-  let args: &[v8::Local<v8::Value>] =
-    std::mem::transmute(std::slice::from_raw_parts(argv, argc));
-  let ret = func.call(env.scope, recv, args).unwrap();
-  let value: v8::Local<v8::Value> = ret.into();
-  *result = std::mem::transmute(value);
+  let func = v8::Local::<v8::Function>::try_from(func)
+    .map_err(|_| Error::FunctionExpected)?;
+  let args = get_args(argc, argv)?;
+  let value = env.try_catch(|scope| func.call(scope, recv, args))?;
+  if !result.is_null() {
+    *result = std::mem::transmute::<v8::Local<v8::Value>, napi_value>(value);
+  }
   Ok(())
 }
@@ -1,7 +1,15 @@
 use crate::ffi::*;
+use crate::napi_create_threadsafe_function::ThreadSafeFunction;
 
 #[napi_sym]
-fn napi_call_threadsafe_function() -> Result {
-  // TODO
-  Ok(())
+fn napi_call_threadsafe_function(
+  func: napi_threadsafe_function,
+  data: *mut c_void,
+  is_blocking: napi_threadsafe_function_call_mode,
+) -> Result {
+  if func.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let tsfn = ThreadSafeFunction::from_handle(func);
+  tsfn.call(data, is_blocking)
 }
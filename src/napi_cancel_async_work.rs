@@ -1,10 +1,31 @@
+use crate::event_loop;
 use crate::ffi::*;
+use crate::napi_create_async_work::{AsyncWork, AsyncWorkState};
 
 #[napi_sym]
 fn napi_cancel_async_work(
   env: napi_env,
   async_work: napi_async_work,
 ) -> Result {
-  // TODO
+  if async_work.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let work = &*(async_work as *const AsyncWork);
+  {
+    let mut state = work.state.lock().unwrap();
+    // Only work that hasn't been picked up by a worker can be cancelled.
+    if *state != AsyncWorkState::Queued {
+      return Err(Error::GenericFailure);
+    }
+    *state = AsyncWorkState::Cancelled;
+  }
+
+  // Like Node, `complete` is still called, asynchronously, with
+  // `napi_cancelled`. The loop ref taken when queueing is released here.
+  let work = work.clone();
+  event_loop::sender().send(Box::new(move |scope| {
+    event_loop::unref_loop();
+    work.run_complete(scope, napi_cancelled);
+  }));
   Ok(())
 }
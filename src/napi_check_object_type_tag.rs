@@ -0,0 +1,36 @@
+use crate::env::Env;
+use crate::ffi::*;
+use crate::napi_type_tag_object::type_tag_private;
+use deno_core::v8;
+
+#[napi_sym]
+fn napi_check_object_type_tag(
+  env: napi_env,
+  object: napi_value,
+  type_tag: *const napi_type_tag,
+  result: *mut bool,
+) -> Result {
+  let env = &mut *(env as *mut Env);
+  if type_tag.is_null() || result.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let object: v8::Local<v8::Value> = transmute(object);
+  let object = v8::Local::<v8::Object>::try_from(object)
+    .map_err(|_| Error::ObjectExpected)?;
+
+  let private = type_tag_private(env.scope);
+  let tag = object.get_private(env.scope, private).unwrap();
+  *result = match v8::Local::<v8::BigInt>::try_from(tag) {
+    Ok(tag) => {
+      // Leading zero words are dropped, so compare against zero-padded
+      // halves.
+      let mut words = [0u64; 2];
+      let (_, words) = tag.to_words_array(&mut words);
+      let type_tag = &*type_tag;
+      words.first().copied().unwrap_or(0) == type_tag.lower
+        && words.get(1).copied().unwrap_or(0) == type_tag.upper
+    }
+    Err(_) => false,
+  };
+  Ok(())
+}
@@ -1,15 +1,8 @@
 use crate::env::Env;
 use crate::ffi::*;
-use deno_core::v8;
 
 #[napi_sym]
 fn napi_close_handle_scope(env: napi_env, scope: napi_handle_scope) -> Result {
   let env = &mut *(env as *mut Env);
-  if env.open_handle_scopes == 0 {
-    return Err(Error::HandleScopeMismatch);
-  }
-  let scope = transmute::<_, &mut v8::HandleScope>(scope);
-  drop(scope);
-  env.open_handle_scopes -= 1;
-  Ok(())
+  env.close_handle_scope(scope as *mut _)
 }
@@ -8,9 +8,10 @@ fn napi_coerce_to_string(
   value: napi_value,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  let coerced = value.to_string(env.scope).unwrap();
+  let coerced = env.try_catch(|scope| value.to_string(scope))?;
   let value: v8::Local<v8::Value> = coerced.into();
   *result = std::mem::transmute(value);
   Ok(())
@@ -1,28 +1,69 @@
-use crate::env::Env;
+use crate::env::{Env, EnvShared};
 use crate::ffi::*;
 use deno_core::v8;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AsyncWorkState {
+  Created,
+  Queued,
+  Running,
+  Completed,
+  Cancelled,
+}
 
 #[repr(C)]
+#[derive(Clone)]
 pub struct AsyncWork {
+  pub env: napi_env,
+  pub shared: *mut EnvShared,
   pub data: *mut c_void,
   pub execute: napi_async_execute_callback,
-  pub complete: napi_async_complete_callback,
+  pub complete: Option<napi_async_complete_callback>,
+  // Shared with the worker thread, which may outlive the allocation
+  // handed to the addon.
+  pub state: Arc<Mutex<AsyncWorkState>>,
 }
 
 unsafe impl Send for AsyncWork {}
 unsafe impl Sync for AsyncWork {}
 
+impl AsyncWork {
+  /// Runs on a worker thread. `execute` gets the module's env, as the
+  /// call that created the work may have returned already. Like in Node,
+  /// it must not be used to call into JS.
+  pub fn run_execute(&self) {
+    unsafe { (self.execute)(self.env, self.data) };
+  }
+
+  /// Runs on the JS thread.
+  pub fn run_complete(
+    &self,
+    scope: &mut v8::ContextScope<v8::HandleScope>,
+    status: napi_status,
+  ) {
+    if let Some(complete) = self.complete {
+      let mut env = Env::with_shared(scope, self.shared);
+      let env_ptr = &mut env as *mut _ as napi_env;
+      unsafe { complete(env_ptr, status, self.data) };
+      env.check_uncaught_exception();
+    }
+  }
+}
+
 #[napi_sym]
 fn napi_create_async_work(
   env: napi_env,
   async_resource: napi_value,
   async_resource_name: napi_value,
-  execute: napi_async_execute_callback,
-  complete: napi_async_complete_callback,
+  execute: Option<napi_async_execute_callback>,
+  complete: Option<napi_async_complete_callback>,
   data: *mut c_void,
   result: *mut napi_async_work,
 ) -> Result {
   let env = &mut *(env as *mut Env);
+  let execute = execute.ok_or(Error::InvalidArg)?;
   let resource = if async_resource.is_null() {
     v8::Object::new(env.scope)
   } else {
@@ -31,10 +72,13 @@ fn napi_create_async_work(
 
   let resource_name: v8::Local<v8::String> = transmute(async_resource_name);
   let mut work = AsyncWork {
+    env: env.shared().module_env,
+    shared: env.shared,
     data,
     execute,
     complete,
+    state: Arc::new(Mutex::new(AsyncWorkState::Created)),
   };
-  *result = transmute::<Box<AsyncWork>, _>(Box::new(work));
+  *result = Box::into_raw(Box::new(work)) as napi_async_work;
   Ok(())
 }
@@ -2,7 +2,7 @@ use crate::env::Env;
 use crate::ffi::*;
 use deno_core::v8;
 
-use crate::util::get_array_buffer_ptr;
+use crate::util::{get_array_buffer_ptr, new_buffer};
 
 #[napi_sym]
 fn napi_create_buffer(
@@ -16,7 +16,7 @@ fn napi_create_buffer(
   if !data.is_null() {
     *data = get_array_buffer_ptr(value);
   }
-  let value = v8::Uint8Array::new(env.scope, value, 0, len).unwrap();
+  let value = new_buffer(env.scope, value, len);
   let value: v8::Local<v8::Value> = value.into();
   *result = std::mem::transmute(value);
   Ok(())
@@ -2,7 +2,7 @@ use crate::env::Env;
 use crate::ffi::*;
 use deno_core::v8;
 
-use crate::util::get_array_buffer_ptr;
+use crate::util::{get_array_buffer_ptr, new_buffer};
 
 #[napi_sym]
 fn napi_create_buffer_copy(
@@ -19,7 +19,7 @@ fn napi_create_buffer_copy(
   if !result_data.is_null() {
     *result_data = ptr;
   }
-  let value = v8::Uint8Array::new(env.scope, value, 0, len).unwrap();
+  let value = new_buffer(env.scope, value, len);
   let value: v8::Local<v8::Value> = value.into();
   *result = std::mem::transmute(value);
   Ok(())
@@ -1,18 +1,27 @@
 use crate::env::Env;
 use crate::ffi::*;
+use crate::weak;
+use crate::weak::Finalizer;
 use deno_core::v8;
 
 #[napi_sym]
 fn napi_create_external(
   env: napi_env,
   value: *mut c_void,
-  finalize_cb: napi_finalize,
+  finalize_cb: Option<napi_finalize>,
   finalize_hint: *mut c_void,
   result: *mut napi_value,
 ) -> Result {
   let mut env = &mut *(env as *mut Env);
-  let value: v8::Local<v8::Value> = v8::External::new(env.scope, value).into();
-  // TODO: finalization
+  let external = v8::External::new(env.scope, value);
+  if finalize_cb.is_some() {
+    let finalizer =
+      Finalizer::new(env.shared, finalize_cb, value, finalize_hint);
+    let object: v8::Local<v8::Value> = external.into();
+    let object = v8::Local::<v8::Object>::try_from(object).unwrap();
+    weak::add_finalizer(env.scope, object, finalizer);
+  }
+  let value: v8::Local<v8::Value> = external.into();
   *result = std::mem::transmute(value);
   Ok(())
 }
@@ -1,5 +1,8 @@
 use crate::env::Env;
+use crate::event_loop;
+use crate::event_loop::LoopSender;
 use crate::ffi::*;
+use crate::weak::Finalizer;
 use deno_core::v8;
 use deno_core::v8::BackingStore;
 use deno_core::v8::UniqueRef;
@@ -18,14 +21,54 @@ extern "C" {
   ) -> *mut BackingStore;
 }
 
+pub struct BackingStoreFinalizer {
+  pub finalizer: Finalizer,
+  pub sender: LoopSender,
+}
+
 pub unsafe extern "C" fn backing_store_deleter_callback(
+  _data: *mut c_void,
+  _byte_length: usize,
+  deleter_data: *mut c_void,
+) {
+  // The memory is owned by the addon, all we can do is tell it that V8 is
+  // done with it.
+  if deleter_data.is_null() {
+    return;
+  }
+  let BackingStoreFinalizer { finalizer, sender } =
+    *Box::from_raw(deleter_data as *mut BackingStoreFinalizer);
+  // V8 may release backing stores from a GC thread.
+  sender.send(Box::new(move |scope| finalizer.run(scope)));
+}
+
+/// Creates a backing store over memory owned by the addon. The finalizer,
+/// if any, is run on the JS thread once V8 releases the store.
+///
+/// # Safety
+///
+/// `data` must point to `byte_length` bytes that stay valid until the
+/// finalizer runs.
+pub unsafe fn new_backing_store(
   data: *mut c_void,
   byte_length: usize,
-  _deleter_data: *mut c_void,
-) {
-  let slice_ptr = ptr::slice_from_raw_parts_mut(data as *mut u8, byte_length);
-  let b = Box::from_raw(slice_ptr);
-  drop(b);
+  finalizer: Finalizer,
+) -> UniqueRef<BackingStore> {
+  let deleter_data = if finalizer.finalize_cb.is_some() {
+    let finalizer = BackingStoreFinalizer {
+      finalizer,
+      sender: event_loop::sender(),
+    };
+    Box::into_raw(Box::new(finalizer)) as *mut c_void
+  } else {
+    ptr::null_mut()
+  };
+  std::mem::transmute(v8__ArrayBuffer__NewBackingStore__with_data(
+    data,
+    byte_length,
+    backing_store_deleter_callback,
+    deleter_data,
+  ))
 }
 
 #[napi_sym]
@@ -33,20 +76,13 @@ fn napi_create_external_arraybuffer(
   env: napi_env,
   data: *mut c_void,
   byte_length: usize,
-  finalize_cb: napi_finalize,
+  finalize_cb: Option<napi_finalize>,
   finalize_hint: *mut c_void,
   result: *mut napi_value,
 ) -> Result {
   let mut env = &mut *(env as *mut Env);
-  let slice = std::slice::from_raw_parts(data as *mut u8, byte_length);
-  // TODO: finalization
-  let store: UniqueRef<BackingStore> =
-    std::mem::transmute(v8__ArrayBuffer__NewBackingStore__with_data(
-      data,
-      byte_length,
-      backing_store_deleter_callback,
-      finalize_hint,
-    ));
+  let finalizer = Finalizer::new(env.shared, finalize_cb, data, finalize_hint);
+  let store = new_backing_store(data, byte_length, finalizer);
 
   let ab = v8::ArrayBuffer::with_backing_store(env.scope, &store.make_shared());
   let value: v8::Local<v8::Value> = ab.into();
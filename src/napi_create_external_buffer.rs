@@ -1,5 +1,8 @@
 use crate::env::Env;
 use crate::ffi::*;
+use crate::napi_create_external_arraybuffer::new_backing_store;
+use crate::util::new_buffer;
+use crate::weak::Finalizer;
 use deno_core::v8;
 
 #[napi_sym]
@@ -7,23 +10,20 @@ fn napi_create_external_buffer(
   env: napi_env,
   byte_length: isize,
   data: *mut c_void,
-  finalize_cb: napi_finalize,
+  finalize_cb: Option<napi_finalize>,
   finalize_hint: *mut c_void,
   result: *mut napi_value,
 ) -> Result {
   let mut env = &mut *(env as *mut Env);
-  let slice = if byte_length == -1 {
-    std::ffi::CStr::from_ptr(data as *const _).to_bytes()
+  let byte_length = if byte_length == -1 {
+    std::ffi::CStr::from_ptr(data as *const _).to_bytes().len()
   } else {
-    std::slice::from_raw_parts(data as *mut u8, byte_length as usize)
+    byte_length as usize
   };
-  // TODO: make this not copy the slice
-  // TODO: finalization
-  let store = v8::ArrayBuffer::new_backing_store_from_boxed_slice(
-    slice.to_vec().into_boxed_slice(),
-  );
+  let finalizer = Finalizer::new(env.shared, finalize_cb, data, finalize_hint);
+  let store = new_backing_store(data, byte_length, finalizer);
   let ab = v8::ArrayBuffer::with_backing_store(env.scope, &store.make_shared());
-  let value = v8::Uint8Array::new(env.scope, ab, 0, slice.len()).unwrap();
+  let value = new_buffer(env.scope, ab, byte_length);
   let value: v8::Local<v8::Value> = value.into();
   *result = std::mem::transmute(value);
   Ok(())
@@ -5,7 +5,7 @@ use deno_core::v8;
 
 #[napi_sym]
 fn napi_create_function(
-  env: &mut Env,
+  env: napi_env,
   name: *const u8,
   length: isize,
   cb: napi_callback,
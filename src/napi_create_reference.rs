@@ -1,16 +1,103 @@
 use crate::env::Env;
 use crate::ffi::*;
-// use deno_core::v8;
+use crate::weak;
+use deno_core::v8;
+
+pub enum ReferenceValue {
+  Strong(v8::Global<v8::Value>),
+  /// Holds a `WeakRef` to the target.
+  Weak(v8::Global<v8::Object>),
+}
+
+/// Holds its value strongly while the count is above zero, and weakly
+/// once it drops to zero. Values that can't be held weakly, like symbols,
+/// stay strong.
+pub struct Reference {
+  pub count: u32,
+  pub value: ReferenceValue,
+}
+
+impl Reference {
+  pub fn new(
+    scope: &mut v8::HandleScope,
+    value: v8::Local<v8::Value>,
+    count: u32,
+  ) -> Self {
+    let mut reference = Self {
+      count,
+      value: ReferenceValue::Strong(v8::Global::new(scope, value)),
+    };
+    if count == 0 {
+      reference.make_weak(scope);
+    }
+    reference
+  }
+
+  /// Returns `None` once a weak target has been collected.
+  pub fn get<'s>(
+    &self,
+    scope: &mut v8::HandleScope<'s>,
+  ) -> Option<v8::Local<'s, v8::Value>> {
+    match &self.value {
+      ReferenceValue::Strong(value) => Some(v8::Local::new(scope, value)),
+      ReferenceValue::Weak(weak) => weak::deref(scope, weak),
+    }
+  }
+
+  pub fn inc(&mut self, scope: &mut v8::HandleScope) -> u32 {
+    self.count += 1;
+    if self.count == 1 {
+      self.make_strong(scope);
+    }
+    self.count
+  }
+
+  pub fn dec(&mut self, scope: &mut v8::HandleScope) -> Option<u32> {
+    if self.count == 0 {
+      return None;
+    }
+    self.count -= 1;
+    if self.count == 0 {
+      self.make_weak(scope);
+    }
+    Some(self.count)
+  }
+
+  fn make_weak(&mut self, scope: &mut v8::HandleScope) {
+    if let ReferenceValue::Strong(value) = &self.value {
+      let value = v8::Local::new(scope, value);
+      if let Ok(object) = v8::Local::<v8::Object>::try_from(value) {
+        self.value = ReferenceValue::Weak(weak::new_weak_ref(scope, object));
+      }
+    }
+  }
+
+  fn make_strong(&mut self, scope: &mut v8::HandleScope) {
+    if let ReferenceValue::Weak(weak) = &self.value {
+      // Stays weak (and empty) if the target is already gone.
+      if let Some(value) = weak::deref(scope, weak) {
+        self.value = ReferenceValue::Strong(v8::Global::new(scope, value));
+      }
+    }
+  }
+}
 
-// TODO: properly implement ref counting stuff
 #[napi_sym]
 fn napi_create_reference(
   env: napi_env,
   value: napi_value,
-  _initial_refcount: u32,
+  initial_refcount: u32,
   result: *mut napi_ref,
 ) -> Result {
-  let mut _env = &mut *(env as *mut Env);
-  *result = value;
+  let env = &mut *(env as *mut Env);
+  if value.is_null() || result.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let value: v8::Local<v8::Value> = transmute(value);
+  if !value.is_object() && !value.is_symbol() {
+    return Err(Error::ObjectExpected);
+  }
+  let reference = Reference::new(env.scope, value, initial_refcount);
+  *result = Box::into_raw(Box::new(reference)) as napi_ref;
   Ok(())
 }
@@ -1,5 +1,229 @@
-use crate::env::Env;
+use crate::env::{Env, EnvShared};
+use crate::event_loop;
+use crate::event_loop::LoopSender;
 use crate::ffi::*;
+use deno_core::v8;
+use std::collections::VecDeque;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+
+pub struct ThreadSafeFunctionState {
+  pub func: Option<v8::Global<v8::Function>>,
+  pub queue: VecDeque<*mut c_void>,
+  pub thread_count: usize,
+  pub is_closing: bool,
+  pub is_closed: bool,
+}
+
+/// Shared between the JS thread and every thread holding the handle. The
+/// handle given to the addon owns one strong count, which is released
+/// once the function is finalized on the JS thread.
+pub struct ThreadSafeFunction {
+  pub env: *mut EnvShared,
+  pub max_queue_size: usize,
+  pub context: *mut c_void,
+  pub call_js_cb: Option<napi_threadsafe_function_call_js>,
+  pub thread_finalize_data: *mut c_void,
+  pub thread_finalize_cb: Option<napi_finalize>,
+  pub state: Mutex<ThreadSafeFunctionState>,
+  pub space_available: Condvar,
+  pub is_ref: AtomicBool,
+  pub sender: LoopSender,
+}
+
+unsafe impl Send for ThreadSafeFunction {}
+unsafe impl Sync for ThreadSafeFunction {}
+
+impl ThreadSafeFunction {
+  /// # Safety
+  ///
+  /// `func` must be a handle returned by `napi_create_threadsafe_function`
+  /// that hasn't been released yet.
+  pub unsafe fn from_handle(func: napi_threadsafe_function) -> Arc<Self> {
+    let ptr = func as *const Self;
+    Arc::increment_strong_count(ptr);
+    Arc::from_raw(ptr)
+  }
+
+  pub fn call(
+    self: &Arc<Self>,
+    data: *mut c_void,
+    mode: napi_threadsafe_function_call_mode,
+  ) -> Result {
+    let mut state = self.state.lock().unwrap();
+    while self.max_queue_size > 0
+      && state.queue.len() >= self.max_queue_size
+      && !state.is_closing
+    {
+      if mode == napi_tsfn_nonblocking {
+        return Err(Error::QueueFull);
+      }
+      state = self.space_available.wait(state).unwrap();
+    }
+
+    if state.is_closing {
+      if state.thread_count == 0 {
+        return Err(Error::InvalidArg);
+      }
+      state.thread_count -= 1;
+      return Err(Error::Closing);
+    }
+
+    state.queue.push_back(data);
+    drop(state);
+    self.schedule_dispatch();
+    Ok(())
+  }
+
+  pub fn acquire(&self) -> Result {
+    let mut state = self.state.lock().unwrap();
+    if state.is_closing {
+      return Err(Error::Closing);
+    }
+    state.thread_count += 1;
+    Ok(())
+  }
+
+  pub fn release(
+    self: &Arc<Self>,
+    mode: napi_threadsafe_function_release_mode,
+  ) -> Result {
+    let mut state = self.state.lock().unwrap();
+    if state.thread_count == 0 {
+      return Err(Error::InvalidArg);
+    }
+    state.thread_count -= 1;
+
+    if (state.thread_count == 0 || mode == napi_tsfn_abort) && !state.is_closing
+    {
+      state.is_closing = mode == napi_tsfn_abort;
+      if state.is_closing && self.max_queue_size > 0 {
+        self.space_available.notify_all();
+      }
+      drop(state);
+      self.schedule_dispatch();
+    }
+    Ok(())
+  }
+
+  /// Must be called from the JS thread.
+  pub fn set_ref(&self, is_ref: bool) {
+    if self.state.lock().unwrap().is_closed {
+      return;
+    }
+    if self.is_ref.swap(is_ref, Ordering::SeqCst) != is_ref {
+      if is_ref {
+        event_loop::ref_loop();
+      } else {
+        event_loop::unref_loop();
+      }
+    }
+  }
+
+  fn schedule_dispatch(self: &Arc<Self>) {
+    let tsfn = Arc::clone(self);
+    self
+      .sender
+      .send(Box::new(move |scope| tsfn.dispatch_one(scope)));
+  }
+
+  fn dispatch_one(&self, scope: &mut v8::ContextScope<v8::HandleScope>) {
+    let mut state = self.state.lock().unwrap();
+    if state.is_closed {
+      return;
+    }
+    if state.is_closing {
+      drop(state);
+      self.finalize(scope);
+      return;
+    }
+
+    let size = state.queue.len();
+    let data = state.queue.pop_front();
+    if data.is_some() && self.max_queue_size > 0 && size == self.max_queue_size
+    {
+      self.space_available.notify_one();
+    }
+    if state.queue.is_empty() && state.thread_count == 0 {
+      state.is_closing = true;
+      if self.max_queue_size > 0 {
+        self.space_available.notify_all();
+      }
+    }
+    let is_closing = state.is_closing;
+    let func = state.func.as_ref().map(|func| v8::Local::new(scope, func));
+    drop(state);
+
+    if let Some(data) = data {
+      let mut env = Env::with_shared(scope, self.env);
+      let env_ptr = &mut env as *mut _ as napi_env;
+      match self.call_js_cb {
+        Some(call_js_cb) => unsafe {
+          let func: napi_value = match func {
+            Some(func) => {
+              let func: v8::Local<v8::Value> = func.into();
+              transmute::<v8::Local<v8::Value>, napi_value>(func)
+            }
+            None => ptr::null_mut(),
+          };
+          call_js_cb(env_ptr, func, self.context, data);
+        },
+        None => {
+          if let Some(func) = func {
+            // Reported below, like an exception left pending by
+            // `call_js_cb`.
+            let _ = env.try_catch(|scope| {
+              let recv = v8::undefined(scope).into();
+              func.call(scope, recv, &[])
+            });
+          }
+        }
+      }
+      env.check_uncaught_exception();
+    }
+
+    if is_closing {
+      self.finalize(scope);
+    }
+  }
+
+  fn finalize(&self, scope: &mut v8::ContextScope<v8::HandleScope>) {
+    let mut state = self.state.lock().unwrap();
+    if state.is_closed {
+      return;
+    }
+    state.is_closed = true;
+    // The global must be dropped on the JS thread.
+    let func = state.func.take();
+    let queue = std::mem::take(&mut state.queue);
+    drop(state);
+    drop(func);
+
+    let mut env = Env::with_shared(scope, self.env);
+    let env_ptr = &mut env as *mut _ as napi_env;
+    unsafe {
+      if let Some(thread_finalize_cb) = self.thread_finalize_cb {
+        thread_finalize_cb(env_ptr, self.thread_finalize_data, self.context);
+      }
+      // Hand the remaining items back to the addon so it can free them.
+      if let Some(call_js_cb) = self.call_js_cb {
+        for data in queue {
+          call_js_cb(ptr::null_mut(), ptr::null_mut(), self.context, data);
+        }
+      }
+    }
+    env.check_uncaught_exception();
+
+    if self.is_ref.swap(false, Ordering::SeqCst) {
+      event_loop::unref_loop();
+    }
+    // Drop the strong count owned by the addon's handle.
+    unsafe { Arc::decrement_strong_count(self as *const Self) };
+  }
+}
 
 #[napi_sym]
 fn napi_create_threadsafe_function(
@@ -10,12 +234,48 @@ fn napi_create_threadsafe_function(
   max_queue_size: usize,
   initial_thread_count: usize,
   thread_finialize_data: *mut c_void,
-  thread_finalize_cb: napi_finalize,
-  context: *const c_void,
-  call_js_cb: napi_threadsafe_function_call_js,
+  thread_finalize_cb: Option<napi_finalize>,
+  context: *mut c_void,
+  call_js_cb: Option<napi_threadsafe_function_call_js>,
   result: *mut napi_threadsafe_function,
 ) -> Result {
   let env = &mut *(env as *mut Env);
-  // TODO
+  if initial_thread_count == 0 {
+    return Err(Error::InvalidArg);
+  }
+
+  let func = if func.is_null() {
+    if call_js_cb.is_none() {
+      return Err(Error::InvalidArg);
+    }
+    None
+  } else {
+    let func: v8::Local<v8::Value> = transmute(func);
+    let func = v8::Local::<v8::Function>::try_from(func)
+      .map_err(|_| Error::FunctionExpected)?;
+    Some(v8::Global::new(env.scope, func))
+  };
+
+  let tsfn = Arc::new(ThreadSafeFunction {
+    env: env.shared,
+    max_queue_size,
+    context,
+    call_js_cb,
+    thread_finalize_data: thread_finialize_data,
+    thread_finalize_cb,
+    state: Mutex::new(ThreadSafeFunctionState {
+      func,
+      queue: VecDeque::new(),
+      thread_count: initial_thread_count,
+      is_closing: false,
+      is_closed: false,
+    }),
+    space_available: Condvar::new(),
+    is_ref: AtomicBool::new(true),
+    sender: event_loop::sender(),
+  });
+  event_loop::ref_loop();
+
+  *result = Arc::into_raw(tsfn) as napi_threadsafe_function;
   Ok(())
 }
@@ -1,5 +1,6 @@
 use crate::env::Env;
 use crate::ffi::*;
+use crate::util::create_error;
 use deno_core::v8;
 
 #[napi_sym]
@@ -9,15 +10,6 @@ fn napi_create_type_error(
   msg: napi_value,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
-
-  let code: v8::Local<v8::Value> = std::mem::transmute(code);
-  let msg: v8::Local<v8::Value> = std::mem::transmute(msg);
-
-  let msg = msg.to_string(env.scope).unwrap();
-
-  let error = v8::Exception::type_error(env.scope, msg);
-  *result = std::mem::transmute(error);
-
-  Ok(())
+  let env = &mut *(env as *mut Env);
+  create_error(env, v8::Exception::type_error, code, msg, result)
 }
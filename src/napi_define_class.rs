@@ -1,6 +1,7 @@
 use crate::env::Env;
 use crate::ffi::*;
 use crate::function::create_function_template;
+use crate::napi_define_properties::define_property;
 use deno_core::v8;
 
 #[napi_sym]
@@ -8,83 +9,56 @@ fn napi_define_class(
   env: napi_env,
   utf8name: *const c_char,
   length: usize,
-  constructor: napi_callback,
+  constructor: Option<napi_callback>,
   callback_data: *mut c_void,
   property_count: usize,
   properties: *const napi_property_descriptor,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
-  let name = std::ffi::CStr::from_ptr(utf8name).to_str().unwrap();
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
+  let constructor = constructor.ok_or(Error::InvalidArg)?;
+  if utf8name.is_null()
+    || result.is_null()
+    || (property_count > 0 && properties.is_null())
+  {
+    return Err(Error::InvalidArg);
+  }
+  let name = if length == usize::MAX {
+    CStr::from_ptr(utf8name).to_str()
+  } else {
+    let name = std::slice::from_raw_parts(utf8name as *const u8, length);
+    std::str::from_utf8(name)
+  }
+  .map_err(|_| Error::InvalidArg)?;
   let tpl: v8::Local<v8::FunctionTemplate> = std::mem::transmute(
     create_function_template(env, Some(name), constructor, callback_data),
   );
-  let napi_properties = std::slice::from_raw_parts(properties, property_count);
+
+  // Members are defined on the instantiated class rather than on the
+  // templates, which lets accessors and values carry the exact attributes
+  // from their descriptors. Static ones go on the constructor itself.
+  let prototype_name = v8::String::new(env.scope, "prototype").unwrap();
+  let (function, prototype) = env.try_catch(|scope| {
+    let function = tpl.get_function(scope)?;
+    let prototype = function.get(scope, prototype_name.into())?;
+    Some((function, prototype.to_object(scope)?))
+  })?;
+  let napi_properties = if property_count > 0 {
+    std::slice::from_raw_parts(properties, property_count)
+  } else {
+    &[]
+  };
   for p in napi_properties {
-    let name = if !p.utf8name.is_null() {
-      let name_str = CStr::from_ptr(p.utf8name).to_str().unwrap();
-      v8::String::new(env.scope, name_str).unwrap()
+    let target = if p.attributes & napi_static != 0 {
+      function.into()
     } else {
-      std::mem::transmute(p.name)
+      prototype
     };
-
-    if !(p.method as *const c_void).is_null() {
-      let function: v8::Local<v8::FunctionTemplate> = std::mem::transmute(
-        create_function_template(env, None, p.method, p.data),
-      );
-      let proto = tpl.prototype_template(env.scope);
-      proto.set(name.into(), function.into());
-    } else if !(p.getter as *const c_void).is_null()
-      || !(p.setter as *const c_void).is_null()
-    {
-      let getter: Option<v8::Local<v8::FunctionTemplate>> =
-        if !(p.getter as *const c_void).is_null() {
-          Some(std::mem::transmute(create_function_template(
-            env, None, p.getter, p.data,
-          )))
-        } else {
-          None
-        };
-      let setter: Option<v8::Local<v8::FunctionTemplate>> =
-        if !(p.setter as *const c_void).is_null() {
-          Some(std::mem::transmute(create_function_template(
-            env, None, p.setter, p.data,
-          )))
-        } else {
-          None
-        };
-
-      let proto = tpl.prototype_template(env.scope);
-
-      let base_name = CStr::from_ptr(p.utf8name).to_str().unwrap();
-      let getter_name =
-        v8::String::new(env.scope, format!("get_{}", base_name).as_str())
-          .unwrap();
-      let setter_name =
-        v8::String::new(env.scope, format!("set_{}", base_name).as_str())
-          .unwrap();
-
-      // TODO: use set_accessor & set_accessor_with_setter
-      match (getter, setter) {
-        (Some(getter), None) => {
-          proto.set(getter_name.into(), getter.into());
-        }
-        (Some(getter), Some(setter)) => {
-          proto.set(getter_name.into(), getter.into());
-          proto.set(setter_name.into(), setter.into());
-        }
-        (None, Some(setter)) => {
-          proto.set(setter_name.into(), setter.into());
-        }
-        (None, None) => unreachable!(),
-      }
-    } else {
-      let proto = tpl.prototype_template(env.scope);
-      proto.set(name.into(), std::mem::transmute(p.value));
-    }
+    define_property(env, target, p)?;
   }
 
-  let value: v8::Local<v8::Value> = tpl.get_function(env.scope).unwrap().into();
+  let value: v8::Local<v8::Value> = function.into();
   *result = std::mem::transmute(value);
   Ok(())
 }
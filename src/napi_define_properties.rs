@@ -1,8 +1,102 @@
+use crate::builtins;
 use crate::env::Env;
 use crate::ffi::*;
 use crate::function::create_function;
 use deno_core::v8;
 
+/// Like `create_function`, but doesn't keep `env` borrowed.
+unsafe fn new_function<'s>(
+  env: &mut Env,
+  name: Option<&str>,
+  cb: napi_callback,
+  data: *mut c_void,
+) -> v8::Local<'s, v8::Value> {
+  let function: v8::Local<v8::Value> =
+    create_function(env, name, cb, data).into();
+  transmute(function)
+}
+
+/// Defines `property` on `object` with `Object.defineProperty`, so that
+/// accessors and values get exactly the attributes the addon asked for.
+///
+/// # Safety
+///
+/// The names, values and callbacks `property` points to must be valid.
+pub unsafe fn define_property(
+  env: &mut Env,
+  object: v8::Local<v8::Object>,
+  property: &napi_property_descriptor,
+) -> Result {
+  let (name, name_str): (v8::Local<v8::Value>, Option<&str>) =
+    if !property.utf8name.is_null() {
+      let name_str = CStr::from_ptr(property.utf8name)
+        .to_str()
+        .map_err(|_| Error::InvalidArg)?;
+      let name = v8::String::new(env.scope, name_str).unwrap();
+      (name.into(), Some(name_str))
+    } else if !property.name.is_null() {
+      let name: v8::Local<v8::Value> = transmute(property.name);
+      if !name.is_name() {
+        return Err(Error::NameExpected);
+      }
+      (name, None)
+    } else {
+      return Err(Error::NameExpected);
+    };
+
+  let mut fields: Vec<(&str, v8::Local<v8::Value>)> = vec![];
+  match (property.getter, property.setter) {
+    (None, None) => {
+      let value = match property.method {
+        Some(method) => new_function(env, name_str, method, property.data),
+        None if !property.value.is_null() => {
+          transmute::<napi_value, v8::Local<v8::Value>>(property.value)
+        }
+        None => v8::undefined(env.scope).into(),
+      };
+      fields.push(("value", value));
+      let writable = property.attributes & napi_writable != 0;
+      fields.push(("writable", v8::Boolean::new(env.scope, writable).into()));
+    }
+    (getter, setter) => {
+      if let Some(getter) = getter {
+        let getter = new_function(env, name_str, getter, property.data);
+        fields.push(("get", getter));
+      }
+      if let Some(setter) = setter {
+        let setter = new_function(env, name_str, setter, property.data);
+        fields.push(("set", setter));
+      }
+    }
+  }
+  let enumerable = property.attributes & napi_enumerable != 0;
+  fields.push(("enumerable", v8::Boolean::new(env.scope, enumerable).into()));
+  let configurable = property.attributes & napi_configurable != 0;
+  fields.push((
+    "configurable",
+    v8::Boolean::new(env.scope, configurable).into(),
+  ));
+
+  // A null prototype keeps `Object.prototype` out of the descriptor, and
+  // creating it with its fields doesn't run any setters.
+  let mut names: Vec<v8::Local<v8::Name>> = vec![];
+  let mut values: Vec<v8::Local<v8::Value>> = vec![];
+  for (field, value) in fields {
+    names.push(v8::String::new(env.scope, field).unwrap().into());
+    values.push(value);
+  }
+  let null = v8::null(env.scope).into();
+  let descriptor =
+    v8::Object::with_prototype_and_properties(env.scope, null, &names, &values);
+
+  let define = builtins::define_property(env.scope);
+  let undefined = v8::undefined(env.scope).into();
+  env.try_catch(|scope| {
+    define.call(scope, undefined, &[object.into(), name, descriptor.into()])
+  })?;
+  Ok(())
+}
+
 #[napi_sym]
 fn napi_define_properties(
   env: napi_env,
@@ -10,26 +104,22 @@ fn napi_define_properties(
   property_count: usize,
   properties: *const napi_property_descriptor,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
+  if property_count > 0 && properties.is_null() {
+    return Err(Error::InvalidArg);
+  }
 
-  let object: v8::Local<v8::Object> = std::mem::transmute(obj);
+  let object: v8::Local<v8::Value> = std::mem::transmute(obj);
+  let object = v8::Local::<v8::Object>::try_from(object)
+    .map_err(|_| Error::ObjectExpected)?;
+  if property_count == 0 {
+    return Ok(());
+  }
   let properties = std::slice::from_raw_parts(properties, property_count);
 
   for property in properties {
-    let name_str = CStr::from_ptr(property.utf8name).to_str().unwrap();
-    let name = v8::String::new(env.scope, name_str).unwrap();
-
-    let method_ptr: *mut c_void = std::mem::transmute(property.method);
-
-    if !method_ptr.is_null() {
-      let function: v8::Local<v8::Value> = {
-        let function =
-          create_function(env, Some(name_str), property.method, property.data);
-        let value: v8::Local<v8::Value> = function.into();
-        std::mem::transmute(value)
-      };
-      object.set(env.scope, name.into(), function).unwrap();
-    }
+    define_property(env, object, property)?;
   }
 
   Ok(())
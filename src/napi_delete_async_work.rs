@@ -1,7 +1,11 @@
 use crate::ffi::*;
+use crate::napi_create_async_work::AsyncWork;
 
 #[napi_sym]
 fn napi_delete_async_work(env: napi_env, work: napi_async_work) -> Result {
-  // TODO
+  if work.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  drop(Box::from_raw(work as *mut AsyncWork));
   Ok(())
 }
@@ -9,11 +9,11 @@ fn napi_delete_property(
   key: napi_value,
   result: *mut bool,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  let obj = value.to_object(env.scope).unwrap();
-  *result = obj
-    .delete(env.scope, std::mem::transmute(key))
-    .unwrap_or(false);
+  let key: v8::Local<v8::Value> = std::mem::transmute(key);
+  *result =
+    env.try_catch(|scope| value.to_object(scope)?.delete(scope, key))?;
   Ok(())
 }
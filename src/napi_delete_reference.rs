@@ -1,10 +1,11 @@
-use crate::env::Env;
 use crate::ffi::*;
-// use deno_core::v8;
+use crate::napi_create_reference::Reference;
 
-// TODO: properly implement ref counting stuff
 #[napi_sym]
 fn napi_delete_reference(env: napi_env, nref: napi_ref) -> Result {
-  let mut _env = &mut *(env as *mut Env);
+  if nref.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  drop(Box::from_raw(nref as *mut Reference));
   Ok(())
 }
@@ -1,4 +1,6 @@
+use crate::env::Env;
 use crate::ffi::*;
+use deno_core::v8;
 
 #[napi_sym]
 fn napi_escape_handle(
@@ -7,6 +9,9 @@ fn napi_escape_handle(
   escapee: napi_value,
   result: *mut napi_value,
 ) -> Result {
-  // TODO
+  let env = &mut *(env as *mut Env);
+  let escapee: v8::Local<v8::Value> = transmute(escapee);
+  let escaped = env.escape_handle(handle_scope as *mut _, escapee)?;
+  *result = transmute::<v8::Local<v8::Value>, napi_value>(escaped);
   Ok(())
 }
@@ -1,7 +1,130 @@
+use crate::builtins;
+use crate::env::Env;
 use crate::ffi::*;
+use deno_core::v8;
+
+/// Canonical array index strings ("0", "1", but not "01") are what V8
+/// reports as numbers.
+fn is_array_index(key: &str) -> bool {
+  match key.parse::<u32>() {
+    Ok(index) => index != u32::MAX && index.to_string() == key,
+    Err(_) => false,
+  }
+}
+
+fn collect_keys<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  object: v8::Local<v8::Object>,
+  key_mode: napi_key_collection_mode,
+  key_filter: napi_key_filter,
+  key_conversion: napi_key_conversion,
+) -> Option<v8::Local<'s, v8::Array>> {
+  let own_keys = builtins::own_keys(scope);
+  let get_descriptor = builtins::get_own_property_descriptor(scope);
+  let undefined = v8::undefined(scope).into();
+  let writable_name = v8::String::new(scope, "writable").unwrap();
+  let enumerable_name = v8::String::new(scope, "enumerable").unwrap();
+  let configurable_name = v8::String::new(scope, "configurable").unwrap();
+
+  let property_filter =
+    napi_key_writable | napi_key_enumerable | napi_key_configurable;
+  let mut keys: Vec<v8::Local<v8::Value>> = vec![];
+  let mut current = object;
+  loop {
+    let own = own_keys.call(scope, undefined, &[current.into()])?;
+    let own = v8::Local::<v8::Array>::try_from(own).ok()?;
+    for i in 0..own.length() {
+      let mut key = own.get_index(scope, i)?;
+      let skip = if key.is_symbol() {
+        napi_key_skip_symbols
+      } else {
+        napi_key_skip_strings
+      };
+      if key_filter & skip != 0 {
+        continue;
+      }
+
+      if key_filter & property_filter != 0 {
+        let descriptor =
+          get_descriptor.call(scope, undefined, &[current.into(), key])?;
+        if descriptor.is_undefined() {
+          continue;
+        }
+        let descriptor = descriptor.to_object(scope)?;
+        // Don't let `Object.prototype` fill in missing fields.
+        let null = v8::null(scope).into();
+        descriptor.set_prototype(scope, null)?;
+        // Like V8, only data properties can be read-only. Accessors have
+        // no `writable` and pass that filter.
+        if key_filter & napi_key_writable != 0
+          && descriptor.get(scope, writable_name.into())?.is_false()
+        {
+          continue;
+        }
+        if key_filter & napi_key_enumerable != 0
+          && !descriptor.get(scope, enumerable_name.into())?.is_true()
+        {
+          continue;
+        }
+        if key_filter & napi_key_configurable != 0
+          && !descriptor.get(scope, configurable_name.into())?.is_true()
+        {
+          continue;
+        }
+      }
+
+      if key_conversion == napi_key_keep_numbers && key.is_string() {
+        let name = key.to_rust_string_lossy(scope);
+        if is_array_index(&name) {
+          key =
+            v8::Number::new(scope, name.parse::<u32>().unwrap() as f64).into();
+        }
+      }
+      // A key shadowed further up the chain is only reported once.
+      if !keys.iter().any(|existing| existing.strict_equals(key)) {
+        keys.push(key);
+      }
+    }
+
+    if key_mode == napi_key_own_only {
+      break;
+    }
+    let prototype = current.get_prototype(scope)?;
+    if !prototype.is_object() {
+      break;
+    }
+    current = prototype.to_object(scope)?;
+  }
+
+  Some(v8::Array::new_with_elements(scope, &keys))
+}
 
 #[napi_sym]
-fn napi_get_all_property_names(env: napi_env) -> Result {
-  // TODO
+fn napi_get_all_property_names(
+  env: napi_env,
+  object: napi_value,
+  key_mode: napi_key_collection_mode,
+  key_filter: napi_key_filter,
+  key_conversion: napi_key_conversion,
+  result: *mut napi_value,
+) -> Result {
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
+  if key_mode != napi_key_include_prototypes && key_mode != napi_key_own_only {
+    return Err(Error::InvalidArg);
+  }
+  if key_conversion != napi_key_keep_numbers
+    && key_conversion != napi_key_numbers_to_strings
+  {
+    return Err(Error::InvalidArg);
+  }
+
+  let object: v8::Local<v8::Value> = transmute(object);
+  let keys = env.try_catch(|scope| {
+    let object = object.to_object(scope)?;
+    collect_keys(scope, object, key_mode, key_filter, key_conversion)
+  })?;
+  let value: v8::Local<v8::Value> = keys.into();
+  *result = transmute::<v8::Local<v8::Value>, napi_value>(value);
   Ok(())
 }
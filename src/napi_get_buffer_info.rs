@@ -1,6 +1,6 @@
 use crate::env::Env;
 use crate::ffi::*;
-use crate::util::get_array_buffer_ptr;
+use crate::util::get_view_data_ptr;
 use deno_core::v8;
 
 #[napi_sym]
@@ -10,17 +10,17 @@ fn napi_get_buffer_info(
   data: *mut *mut u8,
   length: *mut usize,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  let buf = v8::Local::<v8::Uint8Array>::try_from(value).unwrap();
-  let buffer_name = v8::String::new(env.scope, "buffer").unwrap();
-  let abuf = v8::Local::<v8::ArrayBuffer>::try_from(
-    buf.get(env.scope, buffer_name.into()).unwrap(),
-  )
-  .unwrap();
+  let buf = v8::Local::<v8::Uint8Array>::try_from(value)
+    .map_err(|_| Error::InvalidArg)?;
+  // The view may only cover part of its buffer.
   if !data.is_null() {
-    *data = get_array_buffer_ptr(abuf);
+    let abuf = buf.buffer(env.scope).ok_or(Error::GenericFailure)?;
+    *data = get_view_data_ptr(abuf, buf.byte_offset()) as *mut u8;
+  }
+  if !length.is_null() {
+    *length = buf.byte_length();
   }
-  *length = abuf.byte_length();
   Ok(())
 }
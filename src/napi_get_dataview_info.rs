@@ -1,26 +1,40 @@
 use crate::env::Env;
 use crate::ffi::*;
-use crate::util::get_array_buffer_ptr;
+use crate::util::get_view_data_ptr;
 use deno_core::v8;
 
 #[napi_sym]
 fn napi_get_dataview_info(
   env: napi_env,
-  value: napi_value,
-  data: *mut *mut u8,
-  length: *mut usize,
+  dataview: napi_value,
+  byte_length: *mut usize,
+  data: *mut *mut c_void,
+  arraybuffer: *mut napi_value,
+  byte_offset: *mut usize,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
-  let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  let buf = v8::Local::<v8::DataView>::try_from(value).unwrap();
-  let buffer_name = v8::String::new(env.scope, "buffer").unwrap();
-  let abuf = v8::Local::<v8::ArrayBuffer>::try_from(
-    buf.get(env.scope, buffer_name.into()).unwrap(),
-  )
-  .unwrap();
-  if !data.is_null() {
-    *data = get_array_buffer_ptr(abuf);
+  let env = &mut *(env as *mut Env);
+  if dataview.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let value: v8::Local<v8::Value> = transmute(dataview);
+  let view = v8::Local::<v8::DataView>::try_from(value)
+    .map_err(|_| Error::InvalidArg)?;
+
+  if !byte_length.is_null() {
+    *byte_length = view.byte_length();
+  }
+  if !data.is_null() || !arraybuffer.is_null() {
+    let buffer = view.buffer(env.scope).ok_or(Error::GenericFailure)?;
+    if !data.is_null() {
+      *data = get_view_data_ptr(buffer, view.byte_offset());
+    }
+    if !arraybuffer.is_null() {
+      let buffer: v8::Local<v8::Value> = buffer.into();
+      *arraybuffer = transmute::<v8::Local<v8::Value>, napi_value>(buffer);
+    }
+  }
+  if !byte_offset.is_null() {
+    *byte_offset = view.byte_offset();
   }
-  *length = abuf.byte_length();
   Ok(())
 }
@@ -9,10 +9,11 @@ fn napi_get_element(
   index: u32,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let object: v8::Local<v8::Value> = std::mem::transmute(object);
-  let array = v8::Local::<v8::Array>::try_from(object).unwrap();
-  let value: v8::Local<v8::Value> = array.get_index(env.scope, index).unwrap();
+  let value =
+    env.try_catch(|scope| object.to_object(scope)?.get_index(scope, index))?;
   *result = std::mem::transmute(value);
   Ok(())
 }
@@ -1,10 +1,21 @@
+use crate::env::Env;
 use crate::ffi::*;
 
-#[napi_sym]
-fn napi_get_last_error_info(
+/// # Safety
+///
+/// `env` must be null or point to a live `Env`. The returned info is only
+/// valid until the next napi call on `env`.
+// Not a `#[napi_sym]`, as that would overwrite the very error being
+// queried.
+#[no_mangle]
+pub unsafe extern "C" fn napi_get_last_error_info(
   env: napi_env,
   error_code: *mut *const napi_extended_error_info,
-) -> Result {
-  *error_code = std::ptr::null();
-  Ok(())
+) -> napi_status {
+  if env.is_null() || error_code.is_null() {
+    return napi_invalid_arg;
+  }
+  let env = &*(env as *mut Env);
+  *error_code = &env.shared().last_error;
+  napi_ok
 }
@@ -9,15 +9,13 @@ fn napi_get_named_property(
   utf8_name: *const c_char,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let object: v8::Local<v8::Value> = std::mem::transmute(object);
   let utf8_name = std::ffi::CStr::from_ptr(utf8_name);
   let name = v8::String::new(env.scope, &utf8_name.to_string_lossy()).unwrap();
-  let value: v8::Local<v8::Value> = object
-    .to_object(env.scope)
-    .unwrap()
-    .get(env.scope, name.into())
-    .unwrap();
+  let value =
+    env.try_catch(|scope| object.to_object(scope)?.get(scope, name.into()))?;
   *result = std::mem::transmute(value);
   Ok(())
 }
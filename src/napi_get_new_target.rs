@@ -1,6 +1,5 @@
-use crate::env::Env;
 use crate::ffi::*;
-use crate::function::CallbackInfo;
+use crate::function::{new_target, CallbackInfo};
 use deno_core::v8;
 
 #[napi_sym]
@@ -9,12 +8,19 @@ fn napi_get_new_target(
   cbinfo: napi_callback_info,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  if cbinfo.is_null() || result.is_null() {
+    return Err(Error::InvalidArg);
+  }
 
   let cbinfo: &CallbackInfo = &*(cbinfo as *const CallbackInfo);
   let args = &*(cbinfo.args as *const v8::FunctionCallbackArguments);
 
-  // TODO: need v8 binding
+  let new_target = new_target(args);
+  *result = if new_target.is_undefined() {
+    ptr::null_mut()
+  } else {
+    transmute::<v8::Local<v8::Value>, napi_value>(new_target)
+  };
 
   Ok(())
 }
@@ -16,7 +16,7 @@ thread_local! {
 
 #[napi_sym]
 fn napi_get_node_version(
-  _: napi_env,
+  env: napi_env,
   result: *mut *const napi_node_version,
 ) -> Result {
   NODE_VERSION.with(|version| {
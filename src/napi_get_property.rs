@@ -9,11 +9,12 @@ fn napi_get_property(
   key: napi_value,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let object: v8::Local<v8::Value> = std::mem::transmute(object);
-  let object = object.to_object(env.scope).unwrap();
   let key: v8::Local<v8::Value> = std::mem::transmute(key);
-  let value: v8::Local<v8::Value> = object.get(env.scope, key).unwrap();
+  let value =
+    env.try_catch(|scope| object.to_object(scope)?.get(scope, key))?;
   *result = std::mem::transmute(value);
   Ok(())
 }
@@ -8,13 +8,11 @@ fn napi_get_property_names(
   object: napi_value,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let object: v8::Local<v8::Value> = std::mem::transmute(object);
-  let array: v8::Local<v8::Array> = object
-    .to_object(env.scope)
-    .unwrap()
-    .get_property_names(env.scope)
-    .unwrap();
+  let array = env
+    .try_catch(|scope| object.to_object(scope)?.get_property_names(scope))?;
   let value: v8::Local<v8::Value> = array.into();
   *result = std::mem::transmute(value);
   Ok(())
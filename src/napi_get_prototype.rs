@@ -8,10 +8,12 @@ fn napi_get_prototype(
   value: napi_value,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  let obj = value.to_object(env.scope).unwrap();
-  let proto = obj.get_prototype(env.scope).unwrap();
+  // Proxies run their `getPrototypeOf` trap here.
+  let proto =
+    env.try_catch(|scope| value.to_object(scope)?.get_prototype(scope))?;
   *result = std::mem::transmute(proto);
   Ok(())
 }
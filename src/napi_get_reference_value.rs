@@ -1,4 +1,7 @@
+use crate::env::Env;
 use crate::ffi::*;
+use crate::napi_create_reference::Reference;
+use deno_core::v8;
 
 #[napi_sym]
 fn napi_get_reference_value(
@@ -6,7 +9,14 @@ fn napi_get_reference_value(
   reference: napi_ref,
   result: *mut napi_value,
 ) -> Result {
-  // TODO
-  *result = transmute(reference);
+  let env = &mut *(env as *mut Env);
+  if reference.is_null() || result.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let reference = &*(reference as *const Reference);
+  *result = match reference.get(env.scope) {
+    Some(value) => transmute::<v8::Local<v8::Value>, napi_value>(value),
+    None => ptr::null_mut(),
+  };
   Ok(())
 }
@@ -0,0 +1,15 @@
+use crate::ffi::*;
+use crate::napi_create_threadsafe_function::ThreadSafeFunction;
+
+#[napi_sym]
+fn napi_get_threadsafe_function_context(
+  func: napi_threadsafe_function,
+  result: *mut *mut c_void,
+) -> Result {
+  if func.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let tsfn = &*(func as *const ThreadSafeFunction);
+  *result = tsfn.context;
+  Ok(())
+}
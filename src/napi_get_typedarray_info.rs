@@ -1,26 +1,76 @@
 use crate::env::Env;
 use crate::ffi::*;
-use crate::util::get_array_buffer_ptr;
+use crate::util::get_view_data_ptr;
 use deno_core::v8;
 
+fn typedarray_type(
+  value: v8::Local<v8::Value>,
+) -> Option<(napi_typedarray_type, usize)> {
+  let ty = if value.is_int8_array() {
+    (napi_int8_array, 1)
+  } else if value.is_uint8_array() {
+    (napi_uint8_array, 1)
+  } else if value.is_uint8_clamped_array() {
+    (napi_uint8_clamped_array, 1)
+  } else if value.is_int16_array() {
+    (napi_int16_array, 2)
+  } else if value.is_uint16_array() {
+    (napi_uint16_array, 2)
+  } else if value.is_int32_array() {
+    (napi_int32_array, 4)
+  } else if value.is_uint32_array() {
+    (napi_uint32_array, 4)
+  } else if value.is_float32_array() {
+    (napi_float32_array, 4)
+  } else if value.is_float64_array() {
+    (napi_float64_array, 8)
+  } else if value.is_big_int64_array() {
+    (napi_bigint64_array, 8)
+  } else if value.is_big_uint64_array() {
+    (napi_biguint64_array, 8)
+  } else {
+    return None;
+  };
+  Some(ty)
+}
+
 #[napi_sym]
 fn napi_get_typedarray_info(
   env: napi_env,
-  value: napi_value,
-  data: *mut *mut u8,
+  typedarray: napi_value,
+  type_: *mut napi_typedarray_type,
   length: *mut usize,
+  data: *mut *mut c_void,
+  arraybuffer: *mut napi_value,
+  byte_offset: *mut usize,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
-  let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  let buf = v8::Local::<v8::TypedArray>::try_from(value).unwrap();
-  let buffer_name = v8::String::new(env.scope, "buffer").unwrap();
-  let abuf = v8::Local::<v8::ArrayBuffer>::try_from(
-    buf.get(env.scope, buffer_name.into()).unwrap(),
-  )
-  .unwrap();
-  if !data.is_null() {
-    *data = get_array_buffer_ptr(abuf);
+  let env = &mut *(env as *mut Env);
+  if typedarray.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let value: v8::Local<v8::Value> = transmute(typedarray);
+  let (ty, element_size) = typedarray_type(value).ok_or(Error::InvalidArg)?;
+  let array = v8::Local::<v8::TypedArray>::try_from(value)
+    .map_err(|_| Error::InvalidArg)?;
+
+  if !type_.is_null() {
+    *type_ = ty;
+  }
+  if !length.is_null() {
+    *length = array.byte_length() / element_size;
+  }
+  if !data.is_null() || !arraybuffer.is_null() {
+    let buffer = array.buffer(env.scope).ok_or(Error::GenericFailure)?;
+    if !data.is_null() {
+      *data = get_view_data_ptr(buffer, array.byte_offset());
+    }
+    if !arraybuffer.is_null() {
+      let buffer: v8::Local<v8::Value> = buffer.into();
+      *arraybuffer = transmute::<v8::Local<v8::Value>, napi_value>(buffer);
+    }
+  }
+  if !byte_offset.is_null() {
+    *byte_offset = array.byte_offset();
   }
-  *length = abuf.byte_length();
   Ok(())
 }
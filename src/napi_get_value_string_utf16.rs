@@ -10,7 +10,7 @@ fn napi_get_value_string_utf16(
   bufsize: usize,
   result: *mut usize,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
 
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
 
@@ -18,7 +18,7 @@ fn napi_get_value_string_utf16(
     return Err(Error::StringExpected);
   }
 
-  let v8str = value.to_string(env.scope).unwrap();
+  let v8str = env.try_catch(|scope| value.to_string(scope))?;
   let string_len = v8str.length();
 
   if buf.is_null() {
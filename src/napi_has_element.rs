@@ -9,9 +9,10 @@ fn napi_has_element(
   index: u32,
   result: *mut bool,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  let obj = value.to_object(env.scope).unwrap();
-  *result = obj.has_index(env.scope, index).unwrap_or(false);
+  *result =
+    env.try_catch(|scope| value.to_object(scope)?.has_index(scope, index))?;
   Ok(())
 }
@@ -9,11 +9,12 @@ fn napi_has_named_property(
   key: *const c_char,
   result: *mut bool,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let value: v8::Local<v8::Value> = transmute(value);
-  let obj = value.to_object(env.scope).unwrap();
   let key = CStr::from_ptr(key).to_str().unwrap();
   let key = v8::String::new(env.scope, key).unwrap();
-  *result = obj.has(env.scope, key.into()).unwrap_or(false);
+  *result =
+    env.try_catch(|scope| value.to_object(scope)?.has(scope, key.into()))?;
   Ok(())
 }
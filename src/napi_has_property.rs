@@ -9,11 +9,10 @@ fn napi_has_property(
   key: napi_value,
   result: *mut bool,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let value: v8::Local<v8::Value> = transmute(value);
-  let obj = value.to_object(env.scope).unwrap();
-  *result = obj
-    .has(env.scope, std::mem::transmute(key))
-    .unwrap_or(false);
+  let key: v8::Local<v8::Value> = transmute(key);
+  *result = env.try_catch(|scope| value.to_object(scope)?.has(scope, key))?;
   Ok(())
 }
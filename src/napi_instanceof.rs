@@ -1,5 +1,7 @@
+use crate::builtins;
 use crate::env::Env;
 use crate::ffi::*;
+use crate::util::set_error_code;
 use deno_core::v8;
 
 #[napi_sym]
@@ -9,10 +11,30 @@ fn napi_instanceof(
   constructor: napi_value,
   result: *mut bool,
 ) -> Result {
-  let mut env = &mut (env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let value: v8::Local<v8::Value> = transmute(value);
   let constructor: v8::Local<v8::Value> = transmute(constructor);
-  // TODO: what is the rusty v8 API
-  // *result = value.instance_of(constructor);
+
+  // Same error as Node.
+  if !constructor.is_function() {
+    let message =
+      v8::String::new(env.scope, "Constructor must be a function").unwrap();
+    let error = v8::Exception::type_error(env.scope, message);
+    let code = v8::String::new(env.scope, "ERR_NAPI_CONS_FUNCTION").unwrap();
+    set_error_code(env, error, Some(code.into()))?;
+    env.set_pending_exception(error);
+    return Err(Error::FunctionExpected);
+  }
+
+  // Goes through `Symbol.hasInstance`, falling back to walking the
+  // prototype chain, either of which may throw.
+  let instance_of = builtins::instance_of(env.scope);
+  let recv = v8::undefined(env.scope).into();
+  *result = env.try_catch(|scope| {
+    instance_of
+      .call(scope, recv, &[value, constructor])
+      .map(|result| result.is_true())
+  })?;
   Ok(())
 }
@@ -1,4 +1,3 @@
-use crate::env::Env;
 use crate::ffi::*;
 use deno_core::v8;
 
@@ -8,10 +7,12 @@ fn napi_is_buffer(
   value: napi_value,
   result: *mut bool,
 ) -> Result {
-  let mut env = &mut (env as *mut Env);
+  if value.is_null() || result.is_null() {
+    return Err(Error::InvalidArg);
+  }
   let value: v8::Local<v8::Value> = transmute(value);
-  // TODO: should we assume Buffer as Uint8Array in Deno?
-  // or use std/node polyfill?
-  *result = value.is_typed_array();
+  // Buffers are `Uint8Array`s with the `Buffer` prototype, see
+  // `util::new_buffer`.
+  *result = value.is_uint8_array();
   Ok(())
 }
@@ -1,4 +1,3 @@
-use crate::env::Env;
 use crate::ffi::*;
 use deno_core::v8;
 
@@ -8,11 +7,14 @@ fn napi_is_detached_arraybuffer(
   value: napi_value,
   result: *mut bool,
 ) -> Result {
-  let mut env = &mut (env as *mut Env);
+  if value.is_null() || result.is_null() {
+    return Err(Error::InvalidArg);
+  }
   let value: v8::Local<v8::Value> = transmute(value);
-  let ab = v8::Local::<v8::ArrayBuffer>::try_from(value).unwrap();
-  // TODO: what is API for checking if ArrayBuffer is detached?
-  // there's only is_detachable I could find.
-  *result = false;
+  // Same check as Node: a detached buffer no longer has any data.
+  *result = match v8::Local::<v8::ArrayBuffer>::try_from(value) {
+    Ok(ab) => ab.get_backing_store().data().is_none(),
+    Err(_) => false,
+  };
   Ok(())
 }
@@ -1,4 +1,3 @@
-use crate::env::Env;
 use crate::ffi::*;
 use deno_core::v8;
 
@@ -8,9 +7,11 @@ fn napi_is_error(
   value: napi_value,
   result: *mut bool,
 ) -> Result {
-  let mut env = &mut (env as *mut Env);
+  if value.is_null() || result.is_null() {
+    return Err(Error::InvalidArg);
+  }
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  // TODO
-  *result = value.is_object();
+  // Only objects created by an error constructor, not lookalikes.
+  *result = value.is_native_error();
   Ok(())
 }
@@ -3,8 +3,7 @@ use crate::ffi::*;
 
 #[napi_sym]
 fn napi_is_exception_pending(env: napi_env, result: *mut bool) -> Result {
-  let mut env = &mut *(env as *mut Env);
-  // TODO
-  *result = false;
+  let env = &mut *(env as *mut Env);
+  *result = env.shared().last_exception.is_some();
   Ok(())
 }
@@ -1,5 +1,6 @@
 use crate::env::Env;
 use crate::ffi::*;
+use crate::util::get_args;
 use deno_core::v8;
 
 #[napi_sym]
@@ -10,13 +11,14 @@ fn napi_new_instance(
   argv: *const napi_value,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let constructor: v8::Local<v8::Value> = std::mem::transmute(constructor);
-  let constructor = v8::Local::<v8::Function>::try_from(constructor).unwrap();
-  let args: &[v8::Local<v8::Value>] =
-    std::mem::transmute(std::slice::from_raw_parts(argv, argc));
-  let inst = constructor.new_instance(env.scope, args).unwrap();
+  let constructor = v8::Local::<v8::Function>::try_from(constructor)
+    .map_err(|_| Error::FunctionExpected)?;
+  let args = get_args(argc, argv)?;
+  let inst = env.try_catch(|scope| constructor.new_instance(scope, args))?;
   let value: v8::Local<v8::Value> = inst.into();
-  *result = std::mem::transmute(value);
+  *result = std::mem::transmute::<v8::Local<v8::Value>, napi_value>(value);
   Ok(())
 }
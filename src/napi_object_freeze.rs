@@ -0,0 +1,18 @@
+use crate::builtins;
+use crate::env::Env;
+use crate::ffi::*;
+use deno_core::v8;
+
+#[napi_sym]
+fn napi_object_freeze(env: napi_env, object: napi_value) -> Result {
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
+  let object: v8::Local<v8::Value> = transmute(object);
+  let object = v8::Local::<v8::Object>::try_from(object)
+    .map_err(|_| Error::ObjectExpected)?;
+  // Proxy traps run here and may throw.
+  let freeze = builtins::freeze(env.scope);
+  let recv = v8::undefined(env.scope).into();
+  env.try_catch(|scope| freeze.call(scope, recv, &[object.into()]))?;
+  Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::env::Env;
 use crate::ffi::*;
 
 #[napi_sym]
@@ -5,6 +6,7 @@ fn napi_open_escapable_handle_scope(
   env: napi_env,
   result: *mut napi_escapable_handle_scope,
 ) -> Result {
-  // TODO: do this properly
+  let env = &mut *(env as *mut Env);
+  *result = env.open_handle_scope(true) as napi_escapable_handle_scope;
   Ok(())
 }
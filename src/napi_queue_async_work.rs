@@ -1,29 +1,82 @@
-use crate::env::Env;
+use crate::event_loop;
 use crate::ffi::*;
-use crate::napi_create_async_work::AsyncWork;
-use std::sync::Arc;
-use std::sync::Mutex;
+use crate::napi_create_async_work::{AsyncWork, AsyncWorkState};
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex, Once};
+
+/// Same as the default size of libuv's threadpool, which Node runs async
+/// work on. Work queued beyond that waits its turn, and can still be
+/// cancelled until a worker takes it.
+const WORKERS: usize = 4;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static QUEUE: Mutex<VecDeque<Job>> = Mutex::new(VecDeque::new());
+static QUEUED: Condvar = Condvar::new();
+static START_WORKERS: Once = Once::new();
+
+/// Workers take jobs in the order they were queued.
+fn run_worker() {
+  loop {
+    let job = {
+      let mut queue = QUEUE.lock().unwrap();
+      loop {
+        match queue.pop_front() {
+          Some(job) => break job,
+          None => queue = QUEUED.wait(queue).unwrap(),
+        }
+      }
+    };
+    job();
+  }
+}
+
+fn queue_job(job: Job) {
+  START_WORKERS.call_once(|| {
+    for _ in 0..WORKERS {
+      std::thread::spawn(run_worker);
+    }
+  });
+  QUEUE.lock().unwrap().push_back(job);
+  QUEUED.notify_one();
+}
 
 #[napi_sym]
 fn napi_queue_async_work(env: napi_env, work: napi_async_work) -> Result {
-  let env_ptr = &mut *(env as *mut Env);
-  let work = transmute::<napi_async_work, Box<AsyncWork>>(work);
-
-  let data = Arc::new(Mutex::new(work));
-  let (tx, rx) = std::sync::mpsc::channel::<()>();
-
-  let shared = Arc::clone(&data);
-  tokio::task::spawn_blocking(move || {
-    let env = transmute(env_ptr);
-    let work = shared.lock().unwrap();
-    (work.execute)(env, work.data);
-    tx.send(()).unwrap();
-  });
+  if work.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let work = &*(work as *const AsyncWork);
+  {
+    let mut state = work.state.lock().unwrap();
+    if matches!(*state, AsyncWorkState::Queued | AsyncWorkState::Running) {
+      return Err(Error::GenericFailure);
+    }
+    *state = AsyncWorkState::Queued;
+  }
+
+  // The addon may delete the work as soon as `complete` has been called,
+  // so the worker only ever touches its own copy.
+  let work = work.clone();
+  let sender = event_loop::sender();
 
-  // Note: Must be called from the loop thread.
-  // TODO: Don't block the loop thread.
-  rx.recv().unwrap();
-  let work = data.lock().unwrap();
-  (work.complete)(env, napi_ok, work.data);
+  // Keep the loop alive until `complete` has been delivered.
+  event_loop::ref_loop();
+  queue_job(Box::new(move || {
+    {
+      let mut state = work.state.lock().unwrap();
+      if *state != AsyncWorkState::Queued {
+        // Cancelled before it got to run.
+        return;
+      }
+      *state = AsyncWorkState::Running;
+    }
+    work.run_execute();
+    *work.state.lock().unwrap() = AsyncWorkState::Completed;
+    sender.send(Box::new(move |scope| {
+      event_loop::unref_loop();
+      work.run_complete(scope, napi_ok);
+    }));
+  }));
   Ok(())
 }
@@ -1,7 +1,21 @@
+use crate::env::Env;
 use crate::ffi::*;
+use crate::napi_create_reference::Reference;
 
 #[napi_sym]
-fn napi_reference_unref() -> Result {
-  // TODO
+fn napi_reference_unref(
+  env: napi_env,
+  nref: napi_ref,
+  result: *mut u32,
+) -> Result {
+  let env = &mut *(env as *mut Env);
+  if nref.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let reference = &mut *(nref as *mut Reference);
+  let count = reference.dec(env.scope).ok_or(Error::GenericFailure)?;
+  if !result.is_null() {
+    *result = count;
+  }
   Ok(())
 }
@@ -8,10 +8,10 @@ fn napi_reject_deferred(
   deferred: napi_deferred,
   error: napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let resolver: v8::Local<v8::PromiseResolver> = std::mem::transmute(deferred);
-  resolver
-    .reject(env.scope, std::mem::transmute(error))
-    .unwrap();
+  let error: v8::Local<v8::Value> = std::mem::transmute(error);
+  env.try_catch(|scope| resolver.reject(scope, error))?;
   Ok(())
 }
@@ -1,6 +1,14 @@
 use crate::ffi::*;
+use crate::napi_create_threadsafe_function::ThreadSafeFunction;
 
 #[napi_sym]
-fn napi_release_threadsafe_function() -> Result {
-  Ok(())
+fn napi_release_threadsafe_function(
+  func: napi_threadsafe_function,
+  mode: napi_threadsafe_function_release_mode,
+) -> Result {
+  if func.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let tsfn = ThreadSafeFunction::from_handle(func);
+  tsfn.release(mode)
 }
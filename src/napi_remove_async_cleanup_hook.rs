@@ -0,0 +1,12 @@
+use crate::env::{AsyncCleanupHandle, EnvShared};
+use crate::ffi::*;
+
+#[napi_sym]
+fn napi_remove_async_cleanup_hook(
+  remove_handle: napi_async_cleanup_hook_handle,
+) -> Result {
+  if remove_handle.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  EnvShared::remove_async_cleanup_hook(remove_handle as *mut AsyncCleanupHandle)
+}
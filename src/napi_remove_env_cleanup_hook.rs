@@ -1,14 +1,13 @@
-use crate::env::Env;
+use crate::env::{Env, EnvCleanupHook};
 use crate::ffi::*;
-// use deno_core::v8;
 
-// TODO: properly implement
 #[napi_sym]
 fn napi_remove_env_cleanup_hook(
   env: napi_env,
-  hook: extern "C" fn(*const c_void),
+  hook: Option<EnvCleanupHook>,
   data: *const c_void,
 ) -> Result {
-  let mut _env = &mut *(env as *mut Env);
-  Ok(())
+  let env = &mut *(env as *mut Env);
+  let hook = hook.ok_or(Error::InvalidArg)?;
+  env.shared_mut().remove_cleanup_hook(hook, data)
 }
@@ -1,14 +1,28 @@
 use crate::env::{Env, EnvShared};
 use crate::ffi::*;
+use crate::weak;
 use deno_core::v8;
 
 #[napi_sym]
-fn napi_remove_wrap(env: napi_env, value: *mut v8::Value) -> Result {
+fn napi_remove_wrap(
+  env: napi_env,
+  value: napi_value,
+  result: *mut *mut c_void,
+) -> Result {
   let mut env = &mut *(env as *mut Env);
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  let obj = value.to_object(env.scope).unwrap();
+  let obj = v8::Local::<v8::Object>::try_from(value)
+    .map_err(|_| Error::ObjectExpected)?;
   let shared = &*(env.shared as *const EnvShared);
   let napi_wrap = v8::Local::new(env.scope, &shared.napi_wrap);
+  let ext = obj.get_private(env.scope, napi_wrap).unwrap();
+  let ext =
+    v8::Local::<v8::External>::try_from(ext).map_err(|_| Error::InvalidArg)?;
+  // The finalizer is not called once the wrap is removed.
+  let finalizer = weak::cancel_finalizer(env.scope, ext);
   obj.delete_private(env.scope, napi_wrap).unwrap();
+  if !result.is_null() {
+    *result = finalizer.data;
+  }
   Ok(())
 }
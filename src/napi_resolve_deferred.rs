@@ -8,10 +8,10 @@ fn napi_resolve_deferred(
   deferred: napi_deferred,
   result: napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let resolver: v8::Local<v8::PromiseResolver> = std::mem::transmute(deferred);
-  resolver
-    .resolve(env.scope, std::mem::transmute(result))
-    .unwrap();
+  let result: v8::Local<v8::Value> = std::mem::transmute(result);
+  env.try_catch(|scope| resolver.resolve(scope, result))?;
   Ok(())
 }
@@ -8,7 +8,8 @@ fn napi_run_script(
   script: napi_value,
   result: *mut napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
 
   let script: v8::Local<v8::Value> = std::mem::transmute(script);
   if !script.is_string() {
@@ -16,18 +17,8 @@ fn napi_run_script(
   }
   let script = script.to_string(env.scope).unwrap();
 
-  let script = v8::Script::compile(env.scope, script, None);
-  if script.is_none() {
-    return Err(Error::GenericFailure);
-  }
-  let script = script.unwrap();
-  let rv = script.run(env.scope);
-
-  if let Some(rv) = rv {
-    *result = std::mem::transmute(rv);
-  } else {
-    return Err(Error::GenericFailure);
-  }
-
+  let rv = env
+    .try_catch(|scope| v8::Script::compile(scope, script, None)?.run(scope))?;
+  *result = std::mem::transmute::<v8::Local<v8::Value>, napi_value>(rv);
   Ok(())
 }
@@ -9,10 +9,12 @@ fn napi_set_element(
   index: u32,
   value: napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let object: v8::Local<v8::Value> = std::mem::transmute(object);
-  let array = v8::Local::<v8::Array>::try_from(object).unwrap();
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  array.set_index(env.scope, index, value).unwrap();
+  env.try_catch(|scope| {
+    object.to_object(scope)?.set_index(scope, index, value)
+  })?;
   Ok(())
 }
@@ -9,11 +9,12 @@ fn napi_set_property(
   property: napi_value,
   value: napi_value,
 ) -> Result {
-  let mut env = &mut *(env as *mut Env);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
   let object: v8::Local<v8::Value> = std::mem::transmute(object);
-  let object = object.to_object(env.scope).unwrap();
   let property: v8::Local<v8::Value> = std::mem::transmute(property);
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  object.set(env.scope, property, value).unwrap();
+  env
+    .try_catch(|scope| object.to_object(scope)?.set(scope, property, value))?;
   Ok(())
 }
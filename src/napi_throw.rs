@@ -1,10 +1,12 @@
 use crate::env::Env;
 use crate::ffi::*;
+use deno_core::v8;
 
 #[napi_sym]
 fn napi_throw(env: napi_env, error: napi_value) -> Result {
-  let mut env = &mut *(env as *mut Env);
-  let error = transmute(error);
-  env.scope.throw_exception(error);
+  let env = &mut *(env as *mut Env);
+  env.check_pending_exception()?;
+  let error: v8::Local<v8::Value> = transmute(error);
+  env.set_pending_exception(error);
   Ok(())
 }
@@ -0,0 +1,43 @@
+use crate::env::Env;
+use crate::ffi::*;
+use deno_core::v8;
+
+/// Private symbols can't be read or written from JS, so a tag can't be
+/// forged. Shared by every env, like Node's.
+pub fn type_tag_private<'s>(
+  scope: &mut v8::HandleScope<'s>,
+) -> v8::Local<'s, v8::Private> {
+  let name = v8::String::new(scope, "napi_type_tag").unwrap();
+  v8::Private::for_api(scope, Some(name))
+}
+
+#[napi_sym]
+fn napi_type_tag_object(
+  env: napi_env,
+  object: napi_value,
+  type_tag: *const napi_type_tag,
+) -> Result {
+  let env = &mut *(env as *mut Env);
+  if type_tag.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let object: v8::Local<v8::Value> = transmute(object);
+  let object = v8::Local::<v8::Object>::try_from(object)
+    .map_err(|_| Error::ObjectExpected)?;
+
+  let private = type_tag_private(env.scope);
+  if object.has_private(env.scope, private).unwrap_or(false) {
+    return Err(Error::InvalidArg);
+  }
+  let type_tag = &*type_tag;
+  let tag = v8::BigInt::new_from_words(
+    env.scope,
+    false,
+    &[type_tag.lower, type_tag.upper],
+  )
+  .unwrap();
+  object
+    .set_private(env.scope, private, tag.into())
+    .ok_or(Error::GenericFailure)?;
+  Ok(())
+}
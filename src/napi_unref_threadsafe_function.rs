@@ -1,7 +1,15 @@
 use crate::ffi::*;
+use crate::napi_create_threadsafe_function::ThreadSafeFunction;
 
 #[napi_sym]
-fn napi_unref_threadsafe_function() -> Result {
-  // TODO
+fn napi_unref_threadsafe_function(
+  env: napi_env,
+  func: napi_threadsafe_function,
+) -> Result {
+  if func.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let tsfn = &*(func as *const ThreadSafeFunction);
+  tsfn.set_ref(false);
   Ok(())
 }
@@ -1,5 +1,6 @@
 use crate::env::{Env, EnvShared};
 use crate::ffi::*;
+use crate::weak::Finalizer;
 use deno_core::v8;
 
 #[napi_sym]
@@ -10,11 +11,14 @@ fn napi_unwrap(
 ) -> Result {
   let mut env = &mut *(env as *mut Env);
   let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  let obj = value.to_object(env.scope).unwrap();
+  let obj = v8::Local::<v8::Object>::try_from(value)
+    .map_err(|_| Error::ObjectExpected)?;
   let shared = &*(env.shared as *const EnvShared);
   let napi_wrap = v8::Local::new(env.scope, &shared.napi_wrap);
   let ext = obj.get_private(env.scope, napi_wrap).unwrap();
-  let ext = v8::Local::<v8::External>::try_from(ext).unwrap();
-  *result = ext.value();
+  let ext =
+    v8::Local::<v8::External>::try_from(ext).map_err(|_| Error::InvalidArg)?;
+  let finalizer = &*(ext.value() as *const Finalizer);
+  *result = finalizer.data;
   Ok(())
 }
@@ -1,19 +1,43 @@
 use crate::env::{Env, EnvShared};
 use crate::ffi::*;
+use crate::napi_create_reference::Reference;
+use crate::weak;
+use crate::weak::Finalizer;
 use deno_core::v8;
 
 #[napi_sym]
 fn napi_wrap(
   env: napi_env,
-  value: *mut v8::Value,
+  js_object: napi_value,
   native_object: *mut c_void,
+  finalize_cb: Option<napi_finalize>,
+  finalize_hint: *mut c_void,
+  result: *mut napi_ref,
 ) -> Result {
   let mut env = &mut *(env as *mut Env);
-  let value: v8::Local<v8::Value> = std::mem::transmute(value);
-  let obj = value.to_object(env.scope).unwrap();
+  let value: v8::Local<v8::Value> = std::mem::transmute(js_object);
+  let obj = v8::Local::<v8::Object>::try_from(value)
+    .map_err(|_| Error::ObjectExpected)?;
   let shared = &*(env.shared as *const EnvShared);
   let napi_wrap = v8::Local::new(env.scope, &shared.napi_wrap);
-  let ext = v8::External::new(env.scope, native_object);
-  obj.set_private(env.scope, napi_wrap, ext.into());
+  if !obj
+    .get_private(env.scope, napi_wrap)
+    .unwrap()
+    .is_undefined()
+  {
+    return Err(Error::InvalidArg);
+  }
+
+  // The wrap slot holds the finalizer token, which also carries the native
+  // object, so that `napi_remove_wrap` can detach the finalizer.
+  let finalizer =
+    Finalizer::new(env.shared, finalize_cb, native_object, finalize_hint);
+  let token = weak::add_finalizer(env.scope, obj, finalizer);
+  obj.set_private(env.scope, napi_wrap, token.into());
+
+  if !result.is_null() {
+    let reference = Reference::new(env.scope, obj.into(), 0);
+    *result = Box::into_raw(Box::new(reference)) as napi_ref;
+  }
   Ok(())
 }
@@ -1,3 +1,6 @@
+use crate::builtins;
+use crate::env::Env;
+use crate::ffi::*;
 use deno_core::v8;
 use std::cell::Cell;
 
@@ -19,3 +22,146 @@ pub fn get_array_buffer_ptr(ab: v8::Local<v8::ArrayBuffer>) -> *mut u8 {
     unsafe { get_backing_store_slice(&backing_store, 0, byte_length) };
   slice.as_mut_ptr()
 }
+
+/// Returns the address `byte_offset` bytes into the memory of `ab`. Like
+/// in Node, it's null for zero-length and detached buffers, which may
+/// have no memory at all.
+pub fn get_view_data_ptr(
+  ab: v8::Local<v8::ArrayBuffer>,
+  byte_offset: usize,
+) -> *mut c_void {
+  let data = ab
+    .get_backing_store()
+    .data()
+    .map_or(ptr::null_mut(), |data| data.as_ptr() as *mut u8);
+  if data.is_null() {
+    return ptr::null_mut();
+  }
+  unsafe { data.add(byte_offset) as *mut c_void }
+}
+
+/// The arguments of `napi_call_function` and `napi_new_instance`. `argv`
+/// may be null when there are none.
+///
+/// # Safety
+///
+/// Unless null, `argv` must point to `argc` values.
+pub unsafe fn get_args<'a>(
+  argc: usize,
+  argv: *const napi_value,
+) -> std::result::Result<&'a [v8::Local<'a, v8::Value>], Error> {
+  if argc == 0 {
+    return Ok(&[]);
+  }
+  if argv.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  Ok(transmute::<&[napi_value], &[v8::Local<v8::Value>]>(
+    std::slice::from_raw_parts(argv, argc),
+  ))
+}
+
+/// Creates the `Uint8Array` behind an N-API buffer. It gets the
+/// `Buffer` prototype, which is how the host hooks its Buffer
+/// implementation into buffers created by addons.
+pub fn new_buffer<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  ab: v8::Local<v8::ArrayBuffer>,
+  byte_length: usize,
+) -> v8::Local<'s, v8::Uint8Array> {
+  let value = v8::Uint8Array::new(scope, ab, 0, byte_length).unwrap();
+  let prototype = builtins::buffer_prototype(scope);
+  value.set_prototype(scope, prototype.into());
+  value
+}
+
+/// Creates an error of one kind, e.g. `v8::Exception::type_error`.
+pub type ErrorConstructor = for<'s> fn(
+  &mut v8::HandleScope<'s>,
+  v8::Local<v8::String>,
+) -> v8::Local<'s, v8::Value>;
+
+/// Sets `code` as the `code` property of `error`, as Node does for every
+/// kind of error created through N-API.
+pub fn set_error_code(
+  env: &mut Env,
+  error: v8::Local<v8::Value>,
+  code: Option<v8::Local<v8::Value>>,
+) -> Result {
+  if let Some(code) = code {
+    if !code.is_string() {
+      return Err(Error::StringExpected);
+    }
+    let code_key = v8::String::new(env.scope, "code").unwrap();
+    env.try_catch(|scope| {
+      error.to_object(scope)?.set(scope, code_key.into(), code)
+    })?;
+  }
+  Ok(())
+}
+
+/// Shared by `napi_create_error` and its variants.
+///
+/// # Safety
+///
+/// `code` and `msg` must be null or live handles, and `result` must be
+/// null or writable.
+pub unsafe fn create_error(
+  env: &mut Env,
+  new_error: ErrorConstructor,
+  code: napi_value,
+  msg: napi_value,
+  result: *mut napi_value,
+) -> Result {
+  if msg.is_null() || result.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let msg: v8::Local<v8::Value> = transmute(msg);
+  let msg = v8::Local::<v8::String>::try_from(msg)
+    .map_err(|_| Error::StringExpected)?;
+  let code: Option<v8::Local<v8::Value>> = if code.is_null() {
+    None
+  } else {
+    Some(transmute::<napi_value, v8::Local<v8::Value>>(code))
+  };
+
+  let error = new_error(env.scope, msg);
+  set_error_code(env, error, code)?;
+  *result = transmute::<v8::Local<v8::Value>, napi_value>(error);
+  Ok(())
+}
+
+/// Shared by `napi_throw_error` and its variants. The error becomes the
+/// pending exception.
+///
+/// # Safety
+///
+/// `code` and `msg` must be null or NUL-terminated strings.
+pub unsafe fn throw_error(
+  env: &mut Env,
+  new_error: ErrorConstructor,
+  code: *const c_char,
+  msg: *const c_char,
+) -> Result {
+  env.check_pending_exception()?;
+  if msg.is_null() {
+    return Err(Error::InvalidArg);
+  }
+  let msg = CStr::from_ptr(msg)
+    .to_str()
+    .map_err(|_| Error::InvalidArg)?;
+  let msg = v8::String::new(env.scope, msg).unwrap();
+  let code: Option<v8::Local<v8::Value>> = if code.is_null() {
+    None
+  } else {
+    let code = CStr::from_ptr(code)
+      .to_str()
+      .map_err(|_| Error::InvalidArg)?;
+    Some(v8::String::new(env.scope, code).unwrap().into())
+  };
+
+  let error = new_error(env.scope, msg);
+  set_error_code(env, error, code)?;
+  env.set_pending_exception(error);
+  Ok(())
+}
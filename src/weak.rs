@@ -0,0 +1,183 @@
+use crate::builtins::get_function;
+use crate::env::{Env, EnvShared};
+use crate::ffi::*;
+use deno_core::v8;
+use std::cell::RefCell;
+
+// rusty_v8 doesn't expose weak persistent handles or weak callbacks, so
+// both are built on top of the `WeakRef` and `FinalizationRegistry`
+// builtins instead.
+struct Builtins {
+  weak_ref: v8::Global<v8::Function>,
+  deref: v8::Global<v8::Function>,
+  registry: v8::Global<v8::Object>,
+  register: v8::Global<v8::Function>,
+  unregister: v8::Global<v8::Function>,
+}
+
+/// Native finalizer run on the JS thread once the object it is attached
+/// to is gone.
+pub struct Finalizer {
+  pub env: *mut EnvShared,
+  pub finalize_cb: Option<napi_finalize>,
+  pub data: *mut c_void,
+  pub hint: *mut c_void,
+}
+
+unsafe impl Send for Finalizer {}
+
+impl Finalizer {
+  pub fn new(
+    env: *mut EnvShared,
+    finalize_cb: Option<napi_finalize>,
+    data: *mut c_void,
+    hint: *mut c_void,
+  ) -> Self {
+    Self {
+      env,
+      finalize_cb,
+      data,
+      hint,
+    }
+  }
+
+  pub fn run(self, scope: &mut v8::ContextScope<v8::HandleScope>) {
+    if let Some(finalize_cb) = self.finalize_cb {
+      let mut env = Env::with_shared(scope, self.env);
+      let env_ptr = &mut env as *mut _ as napi_env;
+      unsafe { finalize_cb(env_ptr, self.data, self.hint) };
+      env.check_uncaught_exception();
+    }
+  }
+}
+
+thread_local! {
+  static BUILTINS: RefCell<Option<Builtins>> = const { RefCell::new(None) };
+}
+
+fn finalization_callback(
+  scope: &mut v8::HandleScope,
+  args: v8::FunctionCallbackArguments,
+  _rv: v8::ReturnValue,
+) {
+  let token = v8::Local::<v8::External>::try_from(args.get(0)).unwrap();
+  let finalizer = unsafe { *Box::from_raw(token.value() as *mut Finalizer) };
+  let context = scope.get_current_context();
+  let scope = &mut v8::ContextScope::new(scope, context);
+  finalizer.run(scope);
+}
+
+/// Captures the builtins before any user code gets a chance to replace
+/// them. Must be called before loading any module.
+pub fn init(scope: &mut v8::HandleScope) {
+  let context = scope.get_current_context();
+  let global = context.global(scope);
+  let weak_ref = get_function(scope, global, "WeakRef");
+  let prototype_name = v8::String::new(scope, "prototype").unwrap();
+  let prototype = weak_ref.get(scope, prototype_name.into()).unwrap();
+  let prototype = v8::Local::<v8::Object>::try_from(prototype).unwrap();
+  let deref = get_function(scope, prototype, "deref");
+
+  let finalization_registry =
+    get_function(scope, global, "FinalizationRegistry");
+  let prototype = finalization_registry
+    .get(scope, prototype_name.into())
+    .unwrap();
+  let prototype = v8::Local::<v8::Object>::try_from(prototype).unwrap();
+  let register = get_function(scope, prototype, "register");
+  let unregister = get_function(scope, prototype, "unregister");
+  let cleanup = v8::Function::new(scope, finalization_callback).unwrap();
+  let registry = finalization_registry
+    .new_instance(scope, &[cleanup.into()])
+    .unwrap();
+
+  let builtins = Builtins {
+    weak_ref: v8::Global::new(scope, weak_ref),
+    deref: v8::Global::new(scope, deref),
+    registry: v8::Global::new(scope, registry),
+    register: v8::Global::new(scope, register),
+    unregister: v8::Global::new(scope, unregister),
+  };
+  BUILTINS.with(|cell| cell.replace(Some(builtins)));
+}
+
+/// Returns a `WeakRef` that doesn't keep `value` alive.
+pub fn new_weak_ref(
+  scope: &mut v8::HandleScope,
+  value: v8::Local<v8::Object>,
+) -> v8::Global<v8::Object> {
+  BUILTINS.with(|cell| {
+    let builtins = cell.borrow();
+    let builtins = builtins.as_ref().expect("weak::init was not called");
+    let weak_ref = v8::Local::new(scope, &builtins.weak_ref);
+    let weak = weak_ref.new_instance(scope, &[value.into()]).unwrap();
+    v8::Global::new(scope, weak)
+  })
+}
+
+/// Returns the target of a `WeakRef`, or `None` once it was collected.
+pub fn deref<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  weak: &v8::Global<v8::Object>,
+) -> Option<v8::Local<'s, v8::Value>> {
+  BUILTINS.with(|cell| {
+    let builtins = cell.borrow();
+    let builtins = builtins.as_ref().expect("weak::init was not called");
+    let deref = v8::Local::new(scope, &builtins.deref);
+    let weak = v8::Local::new(scope, weak);
+    let value = deref.call(scope, weak.into(), &[]).unwrap();
+    if value.is_undefined() {
+      None
+    } else {
+      Some(value)
+    }
+  })
+}
+
+/// Runs `finalizer` once `object` has been collected. The returned token
+/// can be handed to `cancel_finalizer` to take the finalizer back.
+pub fn add_finalizer<'s>(
+  scope: &mut v8::HandleScope<'s>,
+  object: v8::Local<v8::Object>,
+  finalizer: Finalizer,
+) -> v8::Local<'s, v8::External> {
+  let finalizer = Box::into_raw(Box::new(finalizer));
+  let token = v8::External::new(scope, finalizer as *mut c_void);
+  BUILTINS.with(|cell| {
+    let builtins = cell.borrow();
+    let builtins = builtins.as_ref().expect("weak::init was not called");
+    let registry = v8::Local::new(scope, &builtins.registry);
+    let register = v8::Local::new(scope, &builtins.register);
+    register
+      .call(
+        scope,
+        registry.into(),
+        &[object.into(), token.into(), token.into()],
+      )
+      .unwrap();
+  });
+  token
+}
+
+/// Detaches a finalizer registered with `add_finalizer` without running
+/// it.
+///
+/// # Safety
+///
+/// `token` must have been returned by `add_finalizer`, and its finalizer
+/// must not have run or been cancelled yet.
+pub unsafe fn cancel_finalizer(
+  scope: &mut v8::HandleScope,
+  token: v8::Local<v8::External>,
+) -> Finalizer {
+  BUILTINS.with(|cell| {
+    let builtins = cell.borrow();
+    let builtins = builtins.as_ref().expect("weak::init was not called");
+    let registry = v8::Local::new(scope, &builtins.registry);
+    let unregister = v8::Local::new(scope, &builtins.unregister);
+    unregister
+      .call(scope, registry.into(), &[token.into()])
+      .unwrap();
+  });
+  *Box::from_raw(token.value() as *mut Finalizer)
+}
@@ -4,10 +4,315 @@ print(exports.hello("Rust"));
 print(exports.add(1, 2));
 
 const point = new exports.Point(1, 2);
-print("point.x: " + point.get_x());
-print("point.y: " + point.get_y());
-point.set_x(3);
-print("point.x: " + point.get_x());
+print("point.x: " + point.x);
+print("point.y: " + point.y);
+point.x = 3;
+print("point.x: " + point.x);
+print("point.lengthSquared(): " + point.lengthSquared());
+print("point instanceof Point: " + exports.isInstance(point, exports.Point));
 
-// For testing async
-//  print(exports.readFileAsync("exports.def"));
+class Anything {
+  static [Symbol.hasInstance]() {
+    return true;
+  }
+}
+if (!exports.isInstance(1, Anything)) {
+  throw new Error("isInstance: Symbol.hasInstance was not used");
+}
+
+exports.readFileAsync("exports.def").then((buf) => {
+  print("readFileAsync: " + buf.length + " bytes");
+});
+print("readFileAsync queued");
+
+try {
+  exports.callAndWrap(() => {
+    throw new Error("boom");
+  });
+} catch (e) {
+  print("callAndWrap: " + e.message);
+}
+
+{
+  const invalidArg = 1;
+  const status = exports.callWithNullArgv(() => {
+    throw new Error("callWithNullArgv: callback was called");
+  });
+  if (status !== invalidArg) {
+    throw new Error("callWithNullArgv: expected invalid_arg, got " + status);
+  }
+  print("callWithNullArgv: invalid_arg");
+}
+
+let threadCalls = 0;
+exports.callFromThread(() => {
+  threadCalls++;
+  if (threadCalls === 3) {
+    print("callFromThread: 3 calls");
+  }
+}, 3);
+
+exports.sumAsync(100, (sum) => {
+  if (sum !== 5050) {
+    throw new Error("sumAsync: expected 5050, got " + sum);
+  }
+  print("sumAsync: " + sum);
+});
+
+exports.cancelAsyncWork();
+
+{
+  const includePrototypes = 0, ownOnly = 1;
+  const writable = 1, enumerable = 2, configurable = 4;
+  const skipStrings = 8, skipSymbols = 16;
+  const keepNumbers = 0, numbersToStrings = 1;
+
+  const object = Object.create({ inherited: 1 });
+  object[1] = "a";
+  object.plain = 1;
+  Object.defineProperty(object, "readOnly", {
+    value: 1,
+    enumerable: true,
+    configurable: true,
+  });
+  Object.defineProperty(object, "accessor", {
+    get() {},
+    configurable: true,
+  });
+  object[Symbol.for("symbol")] = 1;
+
+  const formatKey = (key) =>
+    typeof key === "symbol" ? key.toString() : JSON.stringify(key);
+  const format = (keys) => keys.map(formatKey).join(", ");
+  const expectKeys = (name, mode, filter, conversion, expected) => {
+    const keys = format(
+      exports.getAllPropertyNames(object, mode, filter, conversion),
+    );
+    if (keys !== format(expected)) {
+      throw new Error(
+        "getAllPropertyNames " + name + ": expected [" + format(expected) +
+          "], got [" + keys + "]",
+      );
+    }
+    print("getAllPropertyNames " + name + ": [" + keys + "]");
+  };
+
+  expectKeys("own", ownOnly, 0, numbersToStrings, [
+    "1",
+    "plain",
+    "readOnly",
+    "accessor",
+    Symbol.for("symbol"),
+  ]);
+  expectKeys("keep numbers", ownOnly, skipSymbols, keepNumbers, [
+    1,
+    "plain",
+    "readOnly",
+    "accessor",
+  ]);
+  expectKeys("skip strings", ownOnly, skipStrings, numbersToStrings, [
+    Symbol.for("symbol"),
+  ]);
+  expectKeys("writable", ownOnly, writable | skipSymbols, numbersToStrings, [
+    "1",
+    "plain",
+    "accessor",
+  ]);
+  expectKeys("enumerable", ownOnly, enumerable | skipSymbols, keepNumbers, [
+    1,
+    "plain",
+    "readOnly",
+  ]);
+  expectKeys(
+    "configurable",
+    ownOnly,
+    configurable | skipSymbols,
+    numbersToStrings,
+    ["1", "plain", "readOnly", "accessor"],
+  );
+  expectKeys(
+    "prototypes",
+    includePrototypes,
+    enumerable | skipSymbols,
+    numbersToStrings,
+    ["1", "plain", "readOnly", "inherited"],
+  );
+}
+
+for (
+  const [name, buffer] of [
+    ["createBuffer", exports.createBuffer(4)],
+    ["createBufferCopy", exports.createBufferCopy("copy")],
+    ["createExternalBuffer", exports.createExternalBuffer("external")],
+  ]
+) {
+  if (!(buffer instanceof Buffer)) {
+    throw new Error(name + ": expected a Buffer");
+  }
+  print(name + ": Buffer of " + buffer.length + " bytes");
+}
+
+{
+  const escaped = exports.escapeFromOuterScope();
+  if (escaped !== "escaped") {
+    throw new Error("escapeFromOuterScope: got " + escaped);
+  }
+  print("escapeFromOuterScope: " + escaped);
+}
+
+{
+  const NewTarget = exports.createNewTargetFunction();
+  class Derived {}
+  if (
+    NewTarget() !== undefined || new NewTarget() !== NewTarget ||
+    Reflect.construct(NewTarget, [], Derived) !== Derived
+  ) {
+    throw new Error("createNewTargetFunction: wrong new.target");
+  }
+  print("createNewTargetFunction: new.target ok");
+}
+
+{
+  // Assignments only throw in strict mode.
+  const throws = (f) => {
+    try {
+      f();
+    } catch (e) {
+      return e instanceof TypeError;
+    }
+    return false;
+  };
+
+  const frozen = { a: 1 };
+  exports.objectFreeze(frozen);
+  if (
+    !Object.isFrozen(frozen) ||
+    !throws(() => {
+      "use strict";
+      frozen.a = 2;
+    }) ||
+    !throws(() => {
+      "use strict";
+      frozen.b = 1;
+    })
+  ) {
+    throw new Error("objectFreeze: object is still writable");
+  }
+  print("objectFreeze: frozen");
+
+  const sealed = { a: 1 };
+  exports.objectSeal(sealed);
+  sealed.a = 2;
+  if (
+    !Object.isSealed(sealed) || sealed.a !== 2 ||
+    !throws(() => {
+      "use strict";
+      sealed.b = 1;
+    }) ||
+    !throws(() => {
+      "use strict";
+      delete sealed.a;
+    })
+  ) {
+    throw new Error("objectSeal: object is not sealed");
+  }
+  print("objectSeal: sealed");
+
+  const proxy = new Proxy({}, {
+    preventExtensions() {
+      throw new Error("trap");
+    },
+  });
+  try {
+    exports.objectFreeze(proxy);
+    throw new Error("objectFreeze: proxy trap did not throw");
+  } catch (e) {
+    if (e.message !== "trap") throw e;
+  }
+  print("objectFreeze: proxy trap threw");
+}
+
+{
+  const ok = 0, arraybufferExpected = 19, detachableArraybufferExpected = 20;
+
+  const buffer = new ArrayBuffer(8);
+  if (exports.isDetachedArraybuffer(buffer)) {
+    throw new Error("isDetachedArraybuffer: new buffer reported detached");
+  }
+  const status = exports.detachArraybuffer(buffer);
+  if (
+    status !== ok || !exports.isDetachedArraybuffer(buffer) ||
+    buffer.byteLength !== 0
+  ) {
+    throw new Error("detachArraybuffer: buffer was not detached");
+  }
+  print("detachArraybuffer: detached");
+
+  // Memory owned by WebAssembly can't be detached from outside.
+  const memory = new WebAssembly.Memory({ initial: 1 });
+  const statuses = [
+    exports.detachArraybuffer(memory.buffer),
+    exports.detachArraybuffer({}),
+  ];
+  if (
+    statuses[0] !== detachableArraybufferExpected ||
+    statuses[1] !== arraybufferExpected ||
+    exports.isDetachedArraybuffer(memory.buffer)
+  ) {
+    throw new Error("detachArraybuffer: got statuses " + statuses);
+  }
+  print("detachArraybuffer: non-detachable buffers rejected");
+}
+
+{
+  const float64Array = 8;
+  const buffer = new ArrayBuffer(64);
+  const info = exports.getTypedarrayInfo(new Float64Array(buffer, 16, 3));
+  if (
+    info.type !== float64Array || info.length !== 3 ||
+    info.byteOffset !== 16 || info.dataOffset !== 16
+  ) {
+    throw new Error("getTypedarrayInfo: got " + JSON.stringify(info));
+  }
+  print("getTypedarrayInfo: " + JSON.stringify(info));
+}
+
+{
+  const ok = 0, invalidArg = 1;
+  const tagged = {};
+  const statuses = [
+    exports.typeTagObject(tagged, 0),
+    exports.typeTagObject(tagged, 1),
+  ];
+  if (statuses[0] !== ok || statuses[1] !== invalidArg) {
+    throw new Error("typeTagObject: got statuses " + statuses);
+  }
+  if (
+    !exports.checkObjectTypeTag(tagged, 0) ||
+    exports.checkObjectTypeTag(tagged, 1) ||
+    exports.checkObjectTypeTag({}, 0)
+  ) {
+    throw new Error("checkObjectTypeTag: wrong result");
+  }
+  print("typeTagObject: tags checked");
+}
+
+{
+  const ok = 0, stringExpected = 3;
+  try {
+    exports.throwError("ERR_TEST", "thrown");
+    throw new Error("throwError: nothing was thrown");
+  } catch (e) {
+    if (e.code !== "ERR_TEST" || e.message !== "thrown") {
+      throw e;
+    }
+  }
+  const statuses = [
+    exports.createErrorWithCode("ERR_TEST"),
+    exports.createErrorWithCode(1),
+  ];
+  if (statuses[0] !== ok || statuses[1] !== stringExpected) {
+    throw new Error("createErrorWithCode: got statuses " + statuses);
+  }
+  print("throwError: code set");
+}
@@ -0,0 +1,49 @@
+// Run with --expose-gc.
+const exports = dlopen("./example_module/target/release/libexample_module.so");
+
+for (let i = 0; i < 10; i++) {
+  exports.addFinalizer({ i });
+}
+gc();
+
+// Finalizers run from a task after the script, so nothing has been
+// finalized yet. All of them must have run by exit.
+if (exports.finalizedCount() !== 0) {
+  throw new Error("finalizers ran before the script finished");
+}
+exports.expectFinalized(10);
+
+{
+  const ok = 0, objectExpected = 2;
+  if (exports.createReference(1, 1) !== objectExpected) {
+    throw new Error("createReference: expected object_expected for 1");
+  }
+
+  // In a function, so that nothing else holds on to the target.
+  (() => {
+    const target = {};
+    if (exports.createReference(target, 1) !== ok) {
+      throw new Error("createReference: failed");
+    }
+    const counts = [
+      exports.referenceRef(),
+      exports.referenceUnref(),
+      exports.getReferenceValue() === target,
+      exports.referenceUnref(),
+    ];
+    if (counts.join() !== "2,1,true,0") {
+      throw new Error("reference counts: got " + counts.join());
+    }
+  })();
+
+  // A weak target is kept alive until the end of the task that made it
+  // weak, so it's only collected from a later one.
+  exports.sumAsync(1, () => {
+    gc();
+    if (exports.getReferenceValue() !== null) {
+      throw new Error("getReferenceValue: target was not collected");
+    }
+    exports.deleteReference();
+    print("references: target collected");
+  });
+}